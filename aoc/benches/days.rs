@@ -0,0 +1,111 @@
+use common::{Generator, Rng, Solution};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::fs;
+use std::hint::black_box;
+use std::path::PathBuf;
+
+// Number of copies of the real input that each scaled input is made from.
+const SCALES: [usize; 3] = [1, 8, 64];
+
+// Number of commands in the generated input for comparing ways of reading
+// day 2's commands at a size well beyond the scaled copies.
+const GENERATED_COMMANDS: usize = 5_000_000;
+
+fn read_input(day: u32) -> String {
+    let path: PathBuf = [
+        env!("CARGO_MANIFEST_DIR"),
+        "..",
+        &format!("day{}", day),
+        "src",
+        "input.txt",
+    ]
+    .iter()
+    .collect();
+    fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e))
+}
+
+// Build a larger input by repeating the real input.
+fn repeat_input(text: &str, scale: usize) -> String {
+    text.repeat(scale)
+}
+
+// The list of called numbers is shared by every board, so only the boards are
+// repeated.
+fn repeat_bingo_boards(text: &str, scale: usize) -> String {
+    let (numbers, boards) = text.split_once('\n').unwrap();
+    format!("{}\n{}", numbers, boards.repeat(scale))
+}
+
+// The diagnostic report is only meaningful when every number is different, so
+// each copy has its index prepended to every number. The scale must be a power
+// of two so that the prefix bits are evenly split and the life support ratings
+// narrow down to a single copy before reaching the original bits.
+fn repeat_binary_numbers(text: &str, scale: usize) -> String {
+    if scale == 1 {
+        return text.to_string();
+    }
+    let prefix_width = (usize::BITS - (scale - 1).leading_zeros()) as usize;
+    (0..scale)
+        .flat_map(|i| {
+            text.lines()
+                .map(move |line| format!("{:0w$b}{}\n", i, line, w = prefix_width))
+        })
+        .collect()
+}
+
+// Time parsing, part 1 and part 2 separately for each scale of the day's input.
+fn bench_day<S: Solution>(c: &mut Criterion, scale_input: fn(&str, usize) -> String) {
+    let text = read_input(S::DAY);
+    let mut group = c.benchmark_group(format!("day{}", S::DAY));
+    for scale in SCALES {
+        let text = scale_input(&text, scale);
+        let input = S::parse(&text).unwrap();
+        let id = format!("x{}", scale);
+        group.bench_with_input(BenchmarkId::new("parse", &id), &text, |b, text| {
+            b.iter(|| S::parse(black_box(text)))
+        });
+        group.bench_with_input(BenchmarkId::new("part1", &id), &input, |b, input| {
+            b.iter(|| S::part1(black_box(input)))
+        });
+        group.bench_with_input(BenchmarkId::new("part2", &id), &input, |b, input| {
+            b.iter(|| S::part2(black_box(input)))
+        });
+    }
+    group.finish();
+}
+
+// Time reading day 2's commands straight from bytes, which holds only one line
+// at a time, against collecting them from the text with `parse_input`, both on
+// the scaled real input and on a generated one of millions of lines.
+fn bench_day2_stream(c: &mut Criterion) {
+    let mut generated = Vec::new();
+    day2::Day2::generate(&mut Rng::new(2), GENERATED_COMMANDS, &mut generated).unwrap();
+    let inputs = [
+        ("x64", repeat_input(&read_input(2), 64)),
+        ("5M", String::from_utf8(generated).unwrap()),
+    ];
+    let mut group = c.benchmark_group("day2");
+    group.sample_size(20);
+    for (id, text) in &inputs {
+        group.bench_with_input(BenchmarkId::new("stream", id), text, |b, text| {
+            b.iter(|| day2::stream::Commands::new(black_box(text.as_bytes())).count())
+        });
+        group.bench_with_input(BenchmarkId::new("parse_input", id), text, |b, text| {
+            b.iter(|| day2::parse_input(black_box(text)).unwrap().len())
+        });
+    }
+    group.finish();
+}
+
+fn all_days(c: &mut Criterion) {
+    bench_day::<day1::Day1>(c, repeat_input);
+    bench_day::<day2::Day2>(c, repeat_input);
+    bench_day::<day3::Day3>(c, repeat_binary_numbers);
+    bench_day::<day4::Day4>(c, repeat_bingo_boards);
+    bench_day::<day5::Day5>(c, repeat_input);
+    bench_day::<day6::Day6>(c, repeat_input);
+    bench_day::<day7::Day7>(c, repeat_input);
+}
+
+criterion_group!(benches, all_days, bench_day2_stream);
+criterion_main!(benches);
@@ -0,0 +1,54 @@
+use crate::days;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The answer to one part of a day's puzzle, recorded once it was known to be
+/// correct.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RecordedAnswer {
+    pub day: u32,
+    pub part: u32,
+    /// Hash of the input the answer was computed from.
+    pub input_hash: String,
+    pub answer: String,
+}
+
+/// Every recorded answer, stored as a TOML file at the workspace root.
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct Manifest {
+    #[serde(default, rename = "answer")]
+    pub answers: Vec<RecordedAnswer>,
+}
+
+impl Manifest {
+    pub fn default_path() -> PathBuf {
+        days::workspace_root().join("answers.toml")
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+        toml::from_str(&text).map_err(|e| format!("failed to parse {}: {}", path.display(), e))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let text = toml::to_string(self).map_err(|e| e.to_string())?;
+        fs::write(path, text).map_err(|e| format!("failed to write {}: {}", path.display(), e))
+    }
+
+    pub fn find(&self, day: u32, part: u32) -> Option<&RecordedAnswer> {
+        self.answers
+            .iter()
+            .find(|answer| answer.day == day && answer.part == part)
+    }
+}
+
+/// 64-bit FNV-1a hash of the input text, so that an input that has changed can
+/// be told apart from a solution that has broken.
+pub fn input_hash(text: &str) -> String {
+    let hash = text.bytes().fold(0xcbf29ce484222325u64, |hash, b| {
+        (hash ^ b as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("{:016x}", hash)
+}
@@ -0,0 +1,125 @@
+use common::{Generator, ParseError, Rng, Solution};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// One of the two parts of a day's puzzle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn number(self) -> u32 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+
+    pub fn from_number(n: u32) -> Option<Part> {
+        match n {
+            1 => Some(Part::One),
+            2 => Some(Part::Two),
+            _ => None,
+        }
+    }
+}
+
+/// The answer to one part of a puzzle, and how long it took to find.
+pub struct Answer {
+    pub part: Part,
+    pub answer: String,
+    pub elapsed: Duration,
+}
+
+/// Answers to the requested parts of a day's puzzle, and how long the input
+/// took to parse.
+pub struct Solved {
+    pub parse_time: Duration,
+    pub answers: Vec<Answer>,
+}
+
+/// A day's solution with its input and answer types erased, so that every day
+/// can be kept in the same list.
+pub struct Day {
+    pub number: u32,
+    solve: fn(&str, &[Part]) -> Result<Solved, ParseError>,
+    generate: fn(&mut Rng, usize, &mut dyn Write) -> io::Result<()>,
+}
+
+impl Day {
+    const fn new<S: Solution + Generator>() -> Self {
+        Day {
+            number: S::DAY,
+            solve: solve::<S>,
+            generate: S::generate,
+        }
+    }
+
+    /// Parse the input once and return the answer to each of the given parts.
+    pub fn solve(&self, input: &str, parts: &[Part]) -> Result<Solved, ParseError> {
+        (self.solve)(input, parts)
+    }
+
+    /// Write a random input of the given size.
+    pub fn generate(&self, rng: &mut Rng, size: usize, out: &mut dyn Write) -> io::Result<()> {
+        (self.generate)(rng, size, out)
+    }
+
+    /// Path of the puzzle input kept alongside the day's source, independent of
+    /// the current working directory.
+    pub fn default_input_path(&self) -> PathBuf {
+        workspace_root()
+            .join(format!("day{}", self.number))
+            .join("src")
+            .join("input.txt")
+    }
+}
+
+pub fn workspace_root() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap()
+}
+
+fn solve<S: Solution>(input: &str, parts: &[Part]) -> Result<Solved, ParseError> {
+    let start = Instant::now();
+    let input = S::parse(input)?;
+    let parse_time = start.elapsed();
+    let answers = parts
+        .iter()
+        .map(|&part| {
+            let start = Instant::now();
+            let answer = match part {
+                Part::One => S::part1(&input).to_string(),
+                Part::Two => S::part2(&input).to_string(),
+            };
+            Answer {
+                part,
+                answer,
+                elapsed: start.elapsed(),
+            }
+        })
+        .collect();
+    Ok(Solved {
+        parse_time,
+        answers,
+    })
+}
+
+/// Every day that has a solution, in calendar order.
+pub const DAYS: &[Day] = &[
+    Day::new::<day1::Day1>(),
+    Day::new::<day2::Day2>(),
+    Day::new::<day3::Day3>(),
+    Day::new::<day4::Day4>(),
+    Day::new::<day5::Day5>(),
+    Day::new::<day6::Day6>(),
+    Day::new::<day7::Day7>(),
+];
+
+pub fn find(number: u32) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
@@ -0,0 +1,308 @@
+mod answers;
+mod days;
+mod output;
+mod verify;
+
+use answers::Manifest;
+use common::Rng;
+use days::{Day, Part};
+use output::{Format, Record};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Read, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: aoc [run] [--day <N>[,<N>...]] [--part <1|2>] [--input <PATH>] [--format <FORMAT>]
+       aoc verify [--answers <PATH>]
+       aoc record [--answers <PATH>]
+       aoc generate --day <N> [--size <N>] [--seed <N>] [--width <BITS>] [--output <PATH>]
+
+Commands:
+  run       Solve puzzles and print the answers (default)
+  verify    Check every day's answers against the recorded answers
+  record    Solve every day and overwrite the recorded answers
+  generate  Write a random input for a day
+
+Options:
+  --day <N>         Day to run; may be repeated or comma-separated (default: all days)
+  --part <1|2>      Part to run (default: both parts)
+  --input <PATH>    Puzzle input to read, or - for stdin (default: the day's input.txt);
+                    only allowed when running a single day
+  --format <FORMAT> Output format for answers: text, json or csv (default: text)
+  --answers <PATH>  Recorded answers file (default: answers.toml in the workspace root)
+  --size <N>        Number of items, such as lines or numbers, to generate (default: 1000)
+  --seed <N>        Seed for the random number generator (default: 0)
+  --width <BITS>    Width of each binary number (day 3 only, default: 12)
+  --output <PATH>   File to write the generated input to (default: stdout)
+  -h, --help        Print this message";
+
+struct Options {
+    days: Vec<&'static Day>,
+    parts: Vec<Part>,
+    input: Option<String>,
+    format: Format,
+}
+
+struct GenerateOptions {
+    day: &'static Day,
+    size: usize,
+    seed: u64,
+    width: Option<usize>,
+    output: Option<String>,
+}
+
+enum Command {
+    Run(Options),
+    Verify(PathBuf),
+    Record(PathBuf),
+    Generate(GenerateOptions),
+}
+
+fn parse_args(args: Vec<String>) -> Result<Command, String> {
+    let mut args = args.into_iter().peekable();
+    match args.peek().map(String::as_str) {
+        Some("verify") => {
+            args.next();
+            parse_answers_args(args).map(Command::Verify)
+        }
+        Some("record") => {
+            args.next();
+            parse_answers_args(args).map(Command::Record)
+        }
+        Some("generate") => {
+            args.next();
+            parse_generate_args(args).map(Command::Generate)
+        }
+        Some("run") => {
+            args.next();
+            parse_run_args(args).map(Command::Run)
+        }
+        _ => parse_run_args(args).map(Command::Run),
+    }
+}
+
+fn parse_answers_args(mut args: impl Iterator<Item = String>) -> Result<PathBuf, String> {
+    let mut path = Manifest::default_path();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--answers" => {
+                path = args.next().ok_or("--answers requires a value")?.into();
+            }
+            _ => return Err(format!("unexpected argument '{}'", arg)),
+        }
+    }
+    Ok(path)
+}
+
+fn parse_run_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut days = Vec::new();
+    let mut parts = Vec::new();
+    let mut input = None;
+    let mut format = Format::Text;
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| args.next().ok_or(format!("{} requires a value", flag));
+        match arg.as_str() {
+            "--day" => {
+                for s in value("--day")?.split(',') {
+                    let day = s
+                        .parse::<u32>()
+                        .ok()
+                        .and_then(days::find)
+                        .ok_or(format!("no solution for day '{}'", s))?;
+                    days.push(day);
+                }
+            }
+            "--part" => {
+                let s = value("--part")?;
+                let part = s
+                    .parse::<u32>()
+                    .ok()
+                    .and_then(Part::from_number)
+                    .ok_or(format!("invalid part '{}'", s))?;
+                parts.push(part);
+            }
+            "--input" => input = Some(value("--input")?),
+            "--format" => {
+                let s = value("--format")?;
+                format = Format::from_name(&s).ok_or(format!("invalid format '{}'", s))?;
+            }
+            _ => return Err(format!("unexpected argument '{}'", arg)),
+        }
+    }
+    if days.is_empty() {
+        days = days::DAYS.iter().collect();
+    }
+    if parts.is_empty() {
+        parts = Part::ALL.to_vec();
+    }
+    if input.is_some() && days.len() > 1 {
+        return Err("--input can only be used when running a single day".to_string());
+    }
+    Ok(Options {
+        days,
+        parts,
+        input,
+        format,
+    })
+}
+
+fn parse_generate_args(mut args: impl Iterator<Item = String>) -> Result<GenerateOptions, String> {
+    let mut day = None;
+    let mut size = 1000;
+    let mut seed = 0;
+    let mut width = None;
+    let mut output = None;
+    while let Some(arg) = args.next() {
+        let value = args.next().ok_or(format!("{} requires a value", arg));
+        let number = |value: Result<String, String>| {
+            let value = value?;
+            value
+                .parse::<usize>()
+                .map_err(|_| format!("invalid value '{}' for {}", value, arg))
+        };
+        match arg.as_str() {
+            "--day" => {
+                let s = value?;
+                let found = s.parse::<u32>().ok().and_then(days::find);
+                day = Some(found.ok_or(format!("no solution for day '{}'", s))?);
+            }
+            "--size" => size = number(value)?,
+            "--seed" => {
+                let s = value?;
+                seed = s
+                    .parse::<u64>()
+                    .map_err(|_| format!("invalid value '{}' for {}", s, arg))?;
+            }
+            "--width" => width = Some(number(value)?),
+            "--output" => output = Some(value?),
+            _ => return Err(format!("unexpected argument '{}'", arg)),
+        }
+    }
+    let day = day.ok_or("generate requires --day")?;
+    if width.is_some() && day.number != 3 {
+        return Err("--width can only be used with day 3".to_string());
+    }
+    Ok(GenerateOptions {
+        day,
+        size,
+        seed,
+        width,
+        output,
+    })
+}
+
+// Return the name of the input along with its text.
+fn read_input(day: &Day, path: Option<&str>) -> Result<(String, String), String> {
+    match path {
+        Some("-") => {
+            let mut text = String::new();
+            io::stdin()
+                .read_to_string(&mut text)
+                .map_err(|e| format!("failed to read stdin: {}", e))?;
+            Ok(("<stdin>".to_string(), text))
+        }
+        Some(path) => fs::read_to_string(path)
+            .map(|text| (path.to_string(), text))
+            .map_err(|e| format!("failed to read {}: {}", path, e)),
+        None => {
+            let path = day.default_input_path().display().to_string();
+            fs::read_to_string(&path)
+                .map(|text| (path.clone(), text))
+                .map_err(|e| format!("failed to read {}: {}", path, e))
+        }
+    }
+}
+
+fn run_day(day: &Day, options: &Options) -> Result<Vec<Record>, String> {
+    let (name, text) = read_input(day, options.input.as_deref())?;
+    let input_hash = answers::input_hash(&text);
+    let solved = day
+        .solve(&text, &options.parts)
+        .map_err(|e| e.with_file(&name).to_string())?;
+    Ok(solved
+        .answers
+        .into_iter()
+        .map(|answer| Record {
+            day: day.number,
+            part: answer.part.number(),
+            answer: answer.answer,
+            input: name.clone(),
+            input_hash: input_hash.clone(),
+            parse_ns: solved.parse_time.as_nanos() as u64,
+            elapsed_ns: answer.elapsed.as_nanos() as u64,
+        })
+        .collect())
+}
+
+// Return whether every day ran successfully.
+fn run(options: &Options) -> Result<bool, String> {
+    let mut succeeded = true;
+    let mut records = Vec::new();
+    for day in &options.days {
+        match run_day(day, options) {
+            Ok(day_records) => records.extend(day_records),
+            Err(message) => {
+                eprintln!("error: day {}: {}", day.number, message);
+                succeeded = false;
+            }
+        }
+    }
+    output::write(options.format, &records, &mut io::stdout().lock())
+        .map_err(|e| format!("failed to write answers: {}", e))?;
+    Ok(succeeded)
+}
+
+fn generate(options: &GenerateOptions) -> Result<(), String> {
+    let mut out: Box<dyn Write> = match &options.output {
+        Some(path) => Box::new(BufWriter::new(
+            File::create(path).map_err(|e| format!("failed to create {}: {}", path, e))?,
+        )),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+    let mut rng = Rng::new(options.seed);
+    match options.width {
+        Some(width) => day3::generate_report(&mut rng, options.size, width, &mut out),
+        None => options.day.generate(&mut rng, options.size, &mut out),
+    }
+    .and_then(|_| out.flush())
+    .map_err(|e| format!("failed to write input: {}", e))
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+    let command = match parse_args(args) {
+        Ok(command) => command,
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    let result = match command {
+        Command::Run(options) => run(&options),
+        Command::Verify(path) => Manifest::load(&path).map(|manifest| verify::verify(&manifest)),
+        Command::Generate(options) => generate(&options).map(|_| true),
+        Command::Record(path) => verify::record().and_then(|manifest| {
+            manifest.save(&path)?;
+            println!(
+                "Recorded {} answers to {}",
+                manifest.answers.len(),
+                path.display()
+            );
+            Ok(true)
+        }),
+    };
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
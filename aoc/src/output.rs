@@ -0,0 +1,84 @@
+use serde::Serialize;
+use std::io::{self, Write};
+
+/// How the run command prints its answers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+impl Format {
+    pub fn from_name(name: &str) -> Option<Format> {
+        match name {
+            "text" => Some(Format::Text),
+            "json" => Some(Format::Json),
+            "csv" => Some(Format::Csv),
+            _ => None,
+        }
+    }
+}
+
+/// The answer to one part of a day's puzzle along with where it came from and
+/// how long it took.
+#[derive(Serialize, Debug)]
+pub struct Record {
+    pub day: u32,
+    pub part: u32,
+    pub answer: String,
+    pub input: String,
+    pub input_hash: String,
+    /// Time taken to parse the input, in nanoseconds.
+    pub parse_ns: u64,
+    /// Time taken to solve the part after parsing, in nanoseconds.
+    pub elapsed_ns: u64,
+}
+
+const CSV_HEADER: &str = "day,part,answer,input,input_hash,parse_ns,elapsed_ns";
+
+// Quote a CSV field if it contains anything that would otherwise break the row
+// apart.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+pub fn write(format: Format, records: &[Record], out: &mut impl Write) -> io::Result<()> {
+    match format {
+        Format::Text => {
+            let mut day = None;
+            for record in records {
+                if day != Some(record.day) {
+                    writeln!(out, "Day {}", record.day)?;
+                    day = Some(record.day);
+                }
+                writeln!(out, "Part {}: {}", record.part, record.answer)?;
+            }
+        }
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut *out, records)?;
+            writeln!(out)?;
+        }
+        Format::Csv => {
+            writeln!(out, "{}", CSV_HEADER)?;
+            for record in records {
+                writeln!(
+                    out,
+                    "{},{},{},{},{},{},{}",
+                    record.day,
+                    record.part,
+                    csv_field(&record.answer),
+                    csv_field(&record.input),
+                    csv_field(&record.input_hash),
+                    record.parse_ns,
+                    record.elapsed_ns
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use crate::answers::{self, Manifest, RecordedAnswer};
+use crate::days::{self, Day, Part};
+use std::fs;
+use std::panic;
+
+// Solve every part of the day against its own input, returning the hash of the
+// input and the answers. A solution that panics is reported as an error rather
+// than stopping the remaining days from running.
+fn solve_day(day: &Day) -> Result<(String, Vec<String>), String> {
+    let path = day.default_input_path().display().to_string();
+    let text = fs::read_to_string(&path).map_err(|e| format!("failed to read {}: {}", path, e))?;
+    let answers = panic::catch_unwind(|| day.solve(&text, &Part::ALL))
+        .map_err(|_| "solution panicked".to_string())?
+        .map_err(|e| e.with_file(&path).to_string())?
+        .answers
+        .into_iter()
+        .map(|answer| answer.answer)
+        .collect();
+    Ok((answers::input_hash(&text), answers))
+}
+
+/// Check the answer to every part of every day against the manifest, printing
+/// a line for each. Return whether every answer matched.
+pub fn verify(manifest: &Manifest) -> bool {
+    let (mut passed, mut mismatched, mut failed) = (0, 0, 0);
+    for day in days::DAYS {
+        let solved = solve_day(day);
+        for (i, part) in Part::ALL.iter().enumerate() {
+            print!("Day {} Part {}: ", day.number, part.number());
+            let recorded = match manifest.find(day.number, part.number()) {
+                Some(recorded) => recorded,
+                None => {
+                    println!("FAIL no recorded answer");
+                    failed += 1;
+                    continue;
+                }
+            };
+            match &solved {
+                Err(message) => {
+                    println!("FAIL {}", message);
+                    failed += 1;
+                }
+                Ok((input_hash, _)) if *input_hash != recorded.input_hash => {
+                    println!(
+                        "FAIL input hash is {} but the answer was recorded for {}",
+                        input_hash, recorded.input_hash
+                    );
+                    failed += 1;
+                }
+                Ok((_, answers)) if answers[i] != recorded.answer => {
+                    println!("MISMATCH");
+                    println!("  expected: {}", recorded.answer);
+                    println!("     found: {}", answers[i]);
+                    mismatched += 1;
+                }
+                Ok(_) => {
+                    println!("ok");
+                    passed += 1;
+                }
+            }
+        }
+    }
+    println!(
+        "{} passed, {} mismatched, {} failed",
+        passed, mismatched, failed
+    );
+    mismatched == 0 && failed == 0
+}
+
+/// Solve every part of every day and collect the answers into a new manifest.
+pub fn record() -> Result<Manifest, String> {
+    let mut manifest = Manifest::default();
+    for day in days::DAYS {
+        let (input_hash, answers) =
+            solve_day(day).map_err(|message| format!("day {}: {}", day.number, message))?;
+        for (part, answer) in Part::ALL.iter().zip(answers) {
+            manifest.answers.push(RecordedAnswer {
+                day: day.number,
+                part: part.number(),
+                input_hash: input_hash.clone(),
+                answer,
+            });
+        }
+    }
+    Ok(manifest)
+}
@@ -0,0 +1,75 @@
+mod parse;
+mod rng;
+
+pub use parse::{end_of_input, lines, Line, ParseError, ReadError};
+pub use rng::Rng;
+
+use std::fmt::Display;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::ExitCode;
+
+/// A solution to a single day's puzzle. The input is parsed once and then
+/// shared by both parts.
+pub trait Solution {
+    /// Day of the month that the puzzle was released.
+    const DAY: u32;
+
+    /// Problem input after parsing the puzzle text.
+    type Input;
+
+    /// Answer produced by each part of the puzzle.
+    type Answer: Display;
+
+    /// Parse the full text of a puzzle input.
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+
+    fn part1(input: &Self::Input) -> Self::Answer;
+
+    fn part2(input: &Self::Input) -> Self::Answer;
+}
+
+/// Random puzzle inputs for stress testing, written in exactly the format that
+/// the solution parses.
+pub trait Generator {
+    /// Write an input made of `size` items, such as lines or numbers, chosen
+    /// using `rng`.
+    fn generate(rng: &mut Rng, size: usize, out: &mut dyn Write) -> io::Result<()>;
+}
+
+/// Entry point for a single day's binary. Solve the input file named by the
+/// first command-line argument, or `default_input_path` if there isn't one, and
+/// print both answers.
+pub fn run<S: Solution>(default_input_path: &str) -> ExitCode {
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| default_input_path.to_string());
+    let input = match fs::read_to_string(&path) {
+        Ok(text) => S::parse(&text).map_err(|e| e.with_file(&path).to_string()),
+        Err(e) => Err(format!("failed to read {}: {}", path, e)),
+    };
+    match input {
+        Ok(input) => {
+            println!("Part 1: {}", S::part1(&input));
+            println!("Part 2: {}", S::part2(&input));
+            ExitCode::SUCCESS
+        }
+        Err(message) => {
+            eprintln!("error: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Open the input for a day that reads it a line at a time: the named file, or
+/// stdin if `path` is missing or `-`. Returns the name to report errors
+/// against along with the reader.
+pub fn open_input(path: Option<&str>) -> Result<(&str, Box<dyn BufRead>), String> {
+    match path {
+        None | Some("-") => Ok(("<stdin>", Box::new(io::stdin().lock()))),
+        Some(path) => {
+            let file = File::open(path).map_err(|e| format!("failed to read {}: {}", path, e))?;
+            Ok((path, Box::new(BufReader::new(file))))
+        }
+    }
+}
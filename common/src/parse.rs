@@ -0,0 +1,151 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+/// An error found while parsing puzzle input, with enough context to point at
+/// the offending text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Name of the input, if known. Filled in by whoever opened the input.
+    pub file: Option<String>,
+    /// Line number, starting from 1.
+    pub line: usize,
+    /// Column number in characters, starting from 1.
+    pub column: usize,
+    /// Description of what the parser was looking for.
+    pub expected: String,
+    /// The text that was found instead. Empty if the line or input ended.
+    pub found: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, expected: &str, found: &str) -> Self {
+        ParseError {
+            file: None,
+            line,
+            column,
+            expected: expected.to_string(),
+            found: found.to_string(),
+        }
+    }
+
+    pub fn with_file(self, file: &str) -> Self {
+        ParseError {
+            file: Some(file.to_string()),
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}:", file)?;
+        }
+        write!(
+            f,
+            "{}:{}: expected {}, found ",
+            self.line, self.column, self.expected
+        )?;
+        if self.found.is_empty() {
+            write!(f, "nothing")
+        } else {
+            write!(f, "'{}'", self.found)
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// An error from parsing input that is read incrementally rather than all at
+/// once.
+#[derive(Debug)]
+pub enum ReadError {
+    Io(io::Error),
+    Parse(ParseError),
+}
+
+impl ReadError {
+    pub fn with_file(self, file: &str) -> Self {
+        match self {
+            ReadError::Parse(e) => ReadError::Parse(e.with_file(file)),
+            e => e,
+        }
+    }
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReadError::Io(e) => write!(f, "{}", e),
+            ReadError::Parse(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for ReadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ReadError::Io(e) => Some(e),
+            ReadError::Parse(e) => Some(e),
+        }
+    }
+}
+
+impl From<io::Error> for ReadError {
+    fn from(e: io::Error) -> Self {
+        ReadError::Io(e)
+    }
+}
+
+impl From<ParseError> for ReadError {
+    fn from(e: ParseError) -> Self {
+        ReadError::Parse(e)
+    }
+}
+
+/// A line of puzzle input along with its line number, for building errors that
+/// point into the line.
+#[derive(Clone, Copy, Debug)]
+pub struct Line<'a> {
+    pub number: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    /// Column of `field` within the line. `field` must be a slice of the line's
+    /// text, such as one produced by `split`.
+    pub fn column_of(&self, field: &str) -> usize {
+        let offset = field.as_ptr() as usize - self.text.as_ptr() as usize;
+        self.text[..offset].chars().count() + 1
+    }
+
+    /// The empty slice at the end of the line, for reporting missing fields.
+    pub fn end(&self) -> &'a str {
+        &self.text[self.text.len()..]
+    }
+
+    /// Error saying that `expected` was wanted where `field` was found.
+    pub fn error(&self, field: &str, expected: &str) -> ParseError {
+        ParseError::new(self.number, self.column_of(field), expected, field)
+    }
+
+    /// Parse a field of the line, reporting its position if it is invalid.
+    pub fn parse<T: FromStr>(&self, field: &str, expected: &str) -> Result<T, ParseError> {
+        field.parse::<T>().map_err(|_| self.error(field, expected))
+    }
+}
+
+/// Iterate over the lines of the input along with their line numbers.
+pub fn lines(input: &str) -> impl Iterator<Item = Line<'_>> {
+    input.lines().enumerate().map(|(i, text)| Line {
+        number: i + 1,
+        text,
+    })
+}
+
+/// Error for input that ended before something expected was found.
+pub fn end_of_input(input: &str, expected: &str) -> ParseError {
+    ParseError::new(input.lines().count() + 1, 1, expected, "")
+}
@@ -0,0 +1,271 @@
+//! Finding readings that are out of line with their neighbours, such as spikes
+//! and dropouts in a real sonar log, and answering the puzzle without them.
+
+use crate::depth::Depth;
+
+/// How far a reading may stray from its neighbours before it is an outlier.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Method {
+    /// Modified z-score: distance from the neighbours' median in units of
+    /// their median absolute deviation. Robust to other outliers nearby.
+    MedianAbsoluteDeviation,
+    /// Distance from the neighbours' mean in standard deviations.
+    ZScore,
+}
+
+impl Method {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "mad" => Some(Method::MedianAbsoluteDeviation),
+            "zscore" => Some(Method::ZScore),
+            _ => None,
+        }
+    }
+}
+
+/// Compares each reading with the readings up to `window` positions either
+/// side of it, and flags it if its score is beyond `threshold`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Detector {
+    pub window: usize,
+    pub method: Method,
+    pub threshold: f64,
+}
+
+impl Default for Detector {
+    fn default() -> Self {
+        Detector {
+            window: 5,
+            method: Method::MedianAbsoluteDeviation,
+            threshold: 5.0,
+        }
+    }
+}
+
+/// A reading flagged as an outlier. Measurement `index` is on line `index + 1`
+/// of the input.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Anomaly<T> {
+    pub index: usize,
+    pub depth: T,
+    /// The depth its neighbours suggest: their median or mean.
+    pub expected: f64,
+    pub score: f64,
+}
+
+/// What to do with outliers when answering the puzzle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Treatment {
+    /// Drop them, so their neighbours are compared with each other.
+    Exclude,
+    /// Replace them with the depth on the straight line between the nearest
+    /// readings either side that aren't outliers.
+    Interpolate,
+}
+
+impl Treatment {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "exclude" => Some(Treatment::Exclude),
+            "interpolate" => Some(Treatment::Interpolate),
+            _ => None,
+        }
+    }
+}
+
+// Smallest spread a reading is measured against: one unit of depth.
+const MIN_SPREAD: f64 = 1.0;
+
+fn to_f64<T: Depth>(n: T) -> f64 {
+    n.into() as f64
+}
+
+fn median(values: &mut [f64]) -> f64 {
+    values.sort_by(|a, b| a.total_cmp(b));
+    let middle = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (values[middle - 1] + values[middle]) / 2.0
+    } else {
+        values[middle]
+    }
+}
+
+impl Detector {
+    /// Return the outliers in `numbers`, in order.
+    ///
+    /// The spread of the neighbours is taken to be at least 1, the resolution
+    /// of the readings. Otherwise, when most neighbours share a depth, the
+    /// median absolute deviation is 0 and a reading just 1 away would be
+    /// flagged. Among neighbours that agree, a reading is an outlier if it is
+    /// more than `threshold` away from them.
+    pub fn detect<T: Depth>(&self, numbers: &[T]) -> Vec<Anomaly<T>> {
+        let mut anomalies = Vec::new();
+        let mut neighbours = Vec::with_capacity(2 * self.window);
+        for (index, &depth) in numbers.iter().enumerate() {
+            neighbours.clear();
+            let start = index.saturating_sub(self.window);
+            let end = (index + self.window + 1).min(numbers.len());
+            neighbours.extend(numbers[start..index].iter().map(|&n| to_f64(n)));
+            neighbours.extend(numbers[index + 1..end].iter().map(|&n| to_f64(n)));
+            if neighbours.is_empty() {
+                continue;
+            }
+            let (expected, spread) = match self.method {
+                Method::MedianAbsoluteDeviation => {
+                    let m = median(&mut neighbours);
+                    let mut deviations: Vec<f64> =
+                        neighbours.iter().map(|n| (n - m).abs()).collect();
+                    // Scale so the deviation is comparable to a standard
+                    // deviation for normally distributed readings.
+                    (m, 1.4826 * median(&mut deviations))
+                }
+                Method::ZScore => {
+                    let count = neighbours.len() as f64;
+                    let mean = neighbours.iter().sum::<f64>() / count;
+                    let variance =
+                        neighbours.iter().map(|n| (n - mean).powi(2)).sum::<f64>() / count;
+                    (mean, variance.sqrt())
+                }
+            };
+            let distance = (to_f64(depth) - expected).abs();
+            let score = distance / spread.max(MIN_SPREAD);
+            if score > self.threshold {
+                anomalies.push(Anomaly {
+                    index,
+                    depth,
+                    expected,
+                    score,
+                });
+            }
+        }
+        anomalies
+    }
+}
+
+/// Return the readings with the outliers dealt with as `treatment` says.
+/// `anomalies` must be in order, as returned by `Detector::detect`.
+pub fn clean<T: Depth + TryFrom<i128>>(
+    numbers: &[T],
+    anomalies: &[Anomaly<T>],
+    treatment: Treatment,
+) -> Vec<T> {
+    let mut is_outlier = vec![false; numbers.len()];
+    for anomaly in anomalies {
+        is_outlier[anomaly.index] = true;
+    }
+    match treatment {
+        Treatment::Exclude => numbers
+            .iter()
+            .zip(&is_outlier)
+            .filter(|(_, &outlier)| !outlier)
+            .map(|(&n, _)| n)
+            .collect(),
+        Treatment::Interpolate => {
+            let mut cleaned = numbers.to_vec();
+            let mut previous: Option<usize> = None;
+            for i in 0..numbers.len() {
+                if !is_outlier[i] {
+                    previous = Some(i);
+                    continue;
+                }
+                let next = (i + 1..numbers.len()).find(|&j| !is_outlier[j]);
+                cleaned[i] = match (previous, next) {
+                    (Some(p), Some(n)) => {
+                        let fraction = (i - p) as f64 / (n - p) as f64;
+                        let (a, b): (i128, i128) = (numbers[p].into(), numbers[n].into());
+                        // In floating point, as the gap between two `i128`
+                        // depths may not fit in one.
+                        let (x, y) = (a as f64, b as f64);
+                        let depth = (x + (y - x) * fraction).round() as i128;
+                        // Rounding can't take it past either end, so it fits.
+                        T::try_from(depth.clamp(a.min(b), a.max(b)))
+                            .unwrap_or_else(|_| unreachable!("depth between two depths"))
+                    }
+                    (Some(p), None) => numbers[p],
+                    (None, Some(n)) => numbers[n],
+                    (None, None) => numbers[i],
+                };
+            }
+            cleaned
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: [i32; 10] = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+
+    #[test]
+    fn example_has_no_anomalies() {
+        assert_eq!(Detector::default().detect(&EXAMPLE), []);
+    }
+
+    #[test]
+    fn spike_and_dropout() {
+        let numbers = [100, 101, 103, 104, 9000, 106, 108, 109, 0, 112, 113];
+        for method in [Method::MedianAbsoluteDeviation, Method::ZScore] {
+            let detector = Detector {
+                window: 3,
+                method,
+                threshold: 3.0,
+            };
+            let indices: Vec<usize> = detector.detect(&numbers).iter().map(|a| a.index).collect();
+            assert_eq!(indices, [4, 8], "{:?}", method);
+        }
+    }
+
+    #[test]
+    fn mostly_flat() {
+        let numbers = [50, 50, 51, 50, 50, 49, 50, 50, 50, 51, 50, 50, 56, 50, 50];
+        for method in [Method::MedianAbsoluteDeviation, Method::ZScore] {
+            let detector = Detector {
+                method,
+                ..Detector::default()
+            };
+            let anomalies = detector.detect(&numbers);
+            let indices: Vec<usize> = anomalies.iter().map(|a| a.index).collect();
+            assert_eq!(indices, [12], "{:?}", method);
+            assert!(anomalies[0].score.is_finite());
+        }
+    }
+
+    #[test]
+    fn treatments() {
+        let numbers = [100, 101, 103, 104, 9000, 106, 108, 109, 0, 112, 113];
+        let anomalies = Detector::default().detect(&numbers);
+        let excluded = clean(&numbers, &anomalies, Treatment::Exclude);
+        assert_eq!(excluded, [100, 101, 103, 104, 106, 108, 109, 112, 113]);
+        let interpolated = clean(&numbers, &anomalies, Treatment::Interpolate);
+        assert_eq!(
+            interpolated,
+            [100, 101, 103, 104, 105, 106, 108, 109, 111, 112, 113]
+        );
+        assert_eq!(crate::part1(&numbers), 8);
+        assert_eq!(crate::part1(&interpolated), 10);
+    }
+
+    #[test]
+    fn wide_depths() {
+        let base = 1i64 << 40;
+        let numbers: Vec<i64> = [0, 1, 2, 3, 900, 5, 6, 7]
+            .iter()
+            .map(|&n| base + n)
+            .collect();
+        let anomalies = Detector::default().detect(&numbers);
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].depth, base + 900);
+        let interpolated = clean(&numbers, &anomalies, Treatment::Interpolate);
+        assert_eq!(interpolated[4], base + 4);
+        let extremes = [i128::MIN, 0, i128::MAX];
+        let anomalies = [Anomaly {
+            index: 1,
+            depth: 0,
+            expected: 0.0,
+            score: 0.0,
+        }];
+        let interpolated = clean(&extremes, &anomalies, Treatment::Interpolate);
+        assert!(interpolated[1].unsigned_abs() < 1 << 120);
+    }
+}
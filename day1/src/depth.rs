@@ -0,0 +1,38 @@
+//! Integer widths that depth measurements can be read as, for readings or
+//! window sums too large for the puzzle's `i32`.
+
+use common::{Line, ParseError};
+use std::fmt::{Debug, Display};
+use std::str::FromStr;
+
+/// An integer type that depths can be read as. Sums of depths are kept as
+/// `i128` whatever the width of the depths themselves.
+pub trait Depth: Copy + Ord + Debug + Display + FromStr + Into<i128> {
+    /// Name of the type, for error messages.
+    const NAME: &'static str;
+}
+
+macro_rules! impl_depth {
+    ($($t:ty),*) => {
+        $(impl Depth for $t {
+            const NAME: &'static str = stringify!($t);
+        })*
+    };
+}
+
+impl_depth!(i32, i64, i128);
+
+/// Parse the depth that makes up a line, saying so if it is a number too
+/// large for the chosen width rather than not a number at all.
+pub fn parse_depth<T: Depth>(line: &Line) -> Result<T, ParseError> {
+    line.parse::<T>(line.text, "a depth measurement")
+        .map_err(|e| {
+            let digits = line.text.strip_prefix(['-', '+']).unwrap_or(line.text);
+            if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) {
+                let expected = format!("a depth measurement that fits in {}", T::NAME);
+                line.error(line.text, &expected)
+            } else {
+                e
+            }
+        })
+}
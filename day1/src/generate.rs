@@ -0,0 +1,15 @@
+use crate::Day1;
+use common::{Generator, Rng};
+use std::io::{self, Write};
+
+// Depths follow a random walk that trends deeper, like the real sonar sweeps.
+impl Generator for Day1 {
+    fn generate(rng: &mut Rng, size: usize, out: &mut dyn Write) -> io::Result<()> {
+        let mut depth = rng.range(100, 200);
+        for _ in 0..size {
+            writeln!(out, "{}", depth)?;
+            depth = (depth + rng.range(-15, 25)).max(0);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,115 @@
+//! Day 1: Sonar Sweep
+
+pub mod anomaly;
+pub mod depth;
+mod generate;
+pub mod reference;
+pub mod sonar;
+pub mod stream;
+pub mod trend;
+
+use common::{ParseError, Solution};
+use depth::Depth;
+
+/// Parse one depth measurement per line.
+pub fn parse_input(input: &str) -> Result<Vec<i32>, ParseError> {
+    parse_input_as(input)
+}
+
+/// Parse one depth measurement per line, as integers of type `T`.
+pub fn parse_input_as<T: Depth>(input: &str) -> Result<Vec<T>, ParseError> {
+    common::lines(input)
+        .map(|line| depth::parse_depth(&line))
+        .collect()
+}
+
+/// Iterate over each number paired with the number after it.
+pub(crate) fn pairs<T: Copy>(numbers: &[T]) -> impl Iterator<Item = (T, T)> + '_ {
+    numbers.iter().copied().zip(numbers.iter().copied().skip(1))
+}
+
+/// Return number of increases in each number from the previous number.
+pub fn part1<T: Ord + Copy>(numbers: &[T]) -> usize {
+    pairs(numbers).filter(|(a, b)| a < b).count()
+}
+
+/// Return number of increases in each three-measurement sliding window sum from
+/// the previous three-measurement sliding window sum.
+pub fn part2<T: Ord>(numbers: &[T]) -> usize {
+    // Neighbouring windows share two measurements, so only the measurement
+    // entering and the one leaving need comparing.
+    numbers
+        .iter()
+        .zip(numbers.iter().skip(3))
+        .filter(|(a, b)| a < b)
+        .count()
+}
+
+pub struct Day1;
+
+// Depths are read as `i64` so that large generated inputs can be solved too.
+impl Solution for Day1 {
+    const DAY: u32 = 1;
+    type Input = Vec<i64>;
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input_as(input)
+    }
+
+    fn part1(numbers: &Self::Input) -> Self::Answer {
+        part1(numbers)
+    }
+
+    fn part2(numbers: &Self::Input) -> Self::Answer {
+        part2(numbers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{Generator, Rng};
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    const EXAMPLE: &str = include_str!("example.txt");
+
+    #[test]
+    fn part1_example() {
+        let input = Day1::parse(EXAMPLE).unwrap();
+        assert_eq!(Day1::part1(&input), 7);
+    }
+
+    #[test]
+    fn part2_example() {
+        let input = Day1::parse(EXAMPLE).unwrap();
+        assert_eq!(Day1::part2(&input), 5);
+    }
+
+    #[test]
+    fn generated_input_parses() {
+        let mut text = Vec::new();
+        Day1::generate(&mut Rng::new(1), 1000, &mut text).unwrap();
+        Day1::parse(std::str::from_utf8(&text).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn depth_too_wide() {
+        let error = parse_input("199\n3000000000\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+        assert_eq!(error.expected, "a depth measurement that fits in i32");
+        let numbers = parse_input_as::<i64>("199\n3000000000\n").unwrap();
+        assert_eq!(part1(&numbers), 1);
+        let error = parse_input("199\n3x\n").unwrap_err();
+        assert_eq!(error.expected, "a depth measurement");
+    }
+
+    proptest! {
+        #[test]
+        fn parts_match_reference(numbers in vec(-10000..10000, 0..200)) {
+            prop_assert_eq!(part1(&numbers), reference::part1(&numbers));
+            prop_assert_eq!(part2(&numbers), reference::part2(&numbers));
+        }
+    }
+}
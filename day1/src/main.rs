@@ -1,33 +1,118 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use day1::anomaly::{self, Detector, Method, Treatment};
+use day1::stream;
+use day1::trend::TrendReport;
+use std::fs;
+use std::process::ExitCode;
 
-fn read_input(file_path: &str) -> Vec<i32> {
-    let file = File::open(file_path).expect("Failed to read file");
-    let reader = BufReader::new(file);
-    reader
-        .lines()
-        .map(|line| line.unwrap().parse::<i32>().unwrap())
-        .collect()
+const DEFAULT_INPUT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/input.txt");
+
+const USAGE: &str = "\
+Usage: day1 [PATH]
+       day1 stream [PATH|-]
+       day1 report [PATH]
+       day1 anomalies [--window <N>] [--method <mad|zscore>] [--threshold <X>]
+                      [--treat <exclude|interpolate>] [PATH]";
+
+// Read depths as i64, so that readings too deep for the puzzle's i32 can still
+// be reported on.
+fn read_numbers(path: Option<&str>) -> Result<Vec<i64>, String> {
+    let path = path.unwrap_or(DEFAULT_INPUT);
+    match fs::read_to_string(path) {
+        Ok(text) => day1::parse_input_as(&text).map_err(|e| e.with_file(path).to_string()),
+        Err(e) => Err(format!("failed to read {}: {}", path, e)),
+    }
+}
+
+// Answer both parts while reading the input one measurement at a time, from
+// the named file or from stdin if the path is missing or `-`.
+fn run_stream(path: Option<&str>) -> Result<(), String> {
+    let (name, reader) = common::open_input(path)?;
+    let counts = stream::count_stream::<i64>(&stream::parts(), reader)
+        .map_err(|e| e.with_file(name).to_string())?;
+    println!("Part 1: {}", counts[0]);
+    println!("Part 2: {}", counts[1]);
+    Ok(())
 }
 
-/// Return number of increases in each number from the previous number.
-fn part1(numbers: &[i32]) -> usize {
-    numbers
-        .iter()
-        .zip(numbers.iter().skip(1))
-        .filter(|(a, b)| a < b)
-        .count()
+// Print the depth-trend report for the named file, or the default input.
+fn run_report(path: Option<&str>) -> Result<(), String> {
+    print!("{}", TrendReport::new(&read_numbers(path)?));
+    Ok(())
 }
 
-/// Return number of increases in each three-measurement sliding window sum from
-/// the previous three-measurement sliding window sum.
-fn part2(numbers: &[i32]) -> usize {
-    let window_sums: Vec<i32> = numbers.windows(3).map(|w| w.iter().sum()).collect();
-    part1(&window_sums)
+// List the outlying readings, then answer both parts without them if asked.
+fn run_anomalies(mut args: impl Iterator<Item = String>) -> Result<(), String> {
+    let mut detector = Detector::default();
+    let mut treatment = None;
+    let mut path = None;
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("{} requires a value", arg));
+        let invalid = |value: &str| format!("invalid value '{}' for {}", value, arg);
+        match arg.as_str() {
+            "--window" => {
+                let s = value()?;
+                detector.window = s.parse().ok().filter(|&w| w > 0).ok_or(invalid(&s))?;
+            }
+            "--method" => {
+                let s = value()?;
+                detector.method = Method::from_name(&s).ok_or(invalid(&s))?;
+            }
+            "--threshold" => {
+                let s = value()?;
+                detector.threshold = s.parse().map_err(|_| invalid(&s))?;
+            }
+            "--treat" => {
+                let s = value()?;
+                treatment = Some(Treatment::from_name(&s).ok_or(invalid(&s))?);
+            }
+            _ if path.is_none() && !arg.starts_with('-') => path = Some(arg),
+            _ => return Err(format!("unexpected argument '{}'", arg)),
+        }
+    }
+    let numbers = read_numbers(path.as_deref())?;
+    let anomalies = detector.detect(&numbers);
+    for anomaly in &anomalies {
+        println!(
+            "Line {}: depth {}, expected about {:.1} (score {:.1})",
+            anomaly.index + 1,
+            anomaly.depth,
+            anomaly.expected,
+            anomaly.score
+        );
+    }
+    println!(
+        "{} anomalies in {} readings",
+        anomalies.len(),
+        numbers.len()
+    );
+    if let Some(treatment) = treatment {
+        let cleaned = anomaly::clean(&numbers, &anomalies, treatment);
+        println!("Part 1: {}", day1::part1(&cleaned));
+        println!("Part 2: {}", day1::part2(&cleaned));
+    }
+    Ok(())
 }
 
-fn main() {
-    let numbers = read_input("day1/src/input.txt");
-    println!("Part 1: {}", part1(&numbers));
-    println!("Part 2: {}", part2(&numbers));
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1).peekable();
+    let result = match args.peek().map(String::as_str) {
+        Some("-h" | "--help") => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Some("stream") => run_stream(args.nth(1).as_deref()),
+        Some("report") => run_report(args.nth(1).as_deref()),
+        Some("anomalies") => {
+            args.next();
+            run_anomalies(args)
+        }
+        _ => return common::run::<day1::Day1>(DEFAULT_INPUT),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            ExitCode::FAILURE
+        }
+    }
 }
@@ -0,0 +1,25 @@
+//! Straightforward implementations of each part, for checking the real
+//! solutions against.
+
+fn count_increases(sums: &[i32]) -> usize {
+    let mut count = 0;
+    for i in 1..sums.len() {
+        if sums[i] > sums[i - 1] {
+            count += 1;
+        }
+    }
+    count
+}
+
+pub fn part1(numbers: &[i32]) -> usize {
+    count_increases(numbers)
+}
+
+// Sum every window separately rather than using `windows`.
+pub fn part2(numbers: &[i32]) -> usize {
+    let mut sums = Vec::new();
+    for i in 2..numbers.len() {
+        sums.push(numbers[i - 2] + numbers[i - 1] + numbers[i]);
+    }
+    count_increases(&sums)
+}
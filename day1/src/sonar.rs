@@ -0,0 +1,328 @@
+//! Generalised questions about how depth measurements change, for when "how
+//! many increases" isn't enough.
+
+use crate::depth::Depth;
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt;
+
+/// How the measurements in each window are combined into a single value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Aggregation {
+    Sum,
+    Mean,
+    Min,
+    Max,
+}
+
+/// Which changes from one window's value to the next are counted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Comparison {
+    Increase,
+    Decrease,
+    /// A change in either direction by more than the given amount.
+    ChangeBeyond(i64),
+}
+
+/// A question to ask of a series of depth measurements: how many times does
+/// the aggregated value of a sliding window change in the given way?
+///
+/// The fields are private so that every query goes through `new`, which rules
+/// out empty windows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SonarQuery {
+    window: usize,
+    aggregation: Aggregation,
+    comparison: Comparison,
+}
+
+/// Error for a window of measurements whose sum doesn't fit in an `i128`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Overflow {
+    /// Index of the measurement that ends the window.
+    pub index: usize,
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "sum of the window ending at measurement {} doesn't fit in i128",
+            self.index + 1
+        )
+    }
+}
+
+impl Error for Overflow {}
+
+impl SonarQuery {
+    /// The window size must be at least 1.
+    pub fn new(window: usize, aggregation: Aggregation, comparison: Comparison) -> Self {
+        assert!(window > 0, "window size must be at least 1");
+        SonarQuery {
+            window,
+            aggregation,
+            comparison,
+        }
+    }
+
+    /// Number of measurements in each window.
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    pub fn aggregation(&self) -> Aggregation {
+        self.aggregation
+    }
+
+    pub fn comparison(&self) -> Comparison {
+        self.comparison
+    }
+
+    /// Return whether the change from one window value to the next is counted.
+    /// Values are as returned by `SlidingWindow::push`.
+    pub fn is_counted(&self, previous: i128, next: i128) -> bool {
+        match self.comparison {
+            Comparison::Increase => next > previous,
+            Comparison::Decrease => next < previous,
+            Comparison::ChangeBeyond(threshold) => {
+                // Means are kept as sums, so scale the threshold to match.
+                let scale = match self.aggregation {
+                    Aggregation::Mean => self.window as i128,
+                    _ => 1,
+                };
+                // A change too large to represent is beyond any threshold, and
+                // every change, even none, is beyond a negative one.
+                match (
+                    next.checked_sub(previous),
+                    (threshold as i128).checked_mul(scale),
+                ) {
+                    (Some(change), Some(threshold)) => u128::try_from(threshold)
+                        .ok()
+                        .is_none_or(|threshold| change.unsigned_abs() > threshold),
+                    (None, _) => true,
+                    (Some(_), None) => threshold < 0,
+                }
+            }
+        }
+    }
+
+    /// Return the number of windows whose value changed from the previous
+    /// window's value in the way asked about.
+    pub fn count<T: Depth>(&self, numbers: &[T]) -> Result<usize, Overflow> {
+        let mut counter = SonarCounter::new(self);
+        for &n in numbers {
+            counter.push(n)?;
+        }
+        Ok(counter.count())
+    }
+}
+
+/// Answers a query over measurements that are pushed one at a time, holding
+/// only the measurements in the current window.
+pub struct SonarCounter<T> {
+    query: SonarQuery,
+    sliding_window: SlidingWindow<T>,
+    previous: Option<i128>,
+    count: usize,
+}
+
+impl<T: Depth> SonarCounter<T> {
+    pub fn new(query: &SonarQuery) -> Self {
+        SonarCounter {
+            query: *query,
+            sliding_window: SlidingWindow::new(query),
+            previous: None,
+            count: 0,
+        }
+    }
+
+    /// Add the next measurement.
+    pub fn push(&mut self, n: T) -> Result<(), Overflow> {
+        if let Some(value) = self.sliding_window.push(n)? {
+            if self
+                .previous
+                .is_some_and(|previous| self.query.is_counted(previous, value))
+            {
+                self.count += 1;
+            }
+            self.previous = Some(value);
+        }
+        Ok(())
+    }
+
+    /// Number of counted changes among the measurements pushed so far.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+/// Aggregates a sliding window over measurements that are pushed one at a time,
+/// holding only the measurements in the current window.
+pub struct SlidingWindow<T> {
+    window: usize,
+    aggregation: Aggregation,
+    measurements: VecDeque<T>,
+    sum: i128,
+    // Candidates for the window's minimum or maximum along with their position
+    // in the series, in order, with the current extreme at the front.
+    extremes: VecDeque<(usize, T)>,
+    pushed: usize,
+}
+
+impl<T: Depth> SlidingWindow<T> {
+    pub fn new(query: &SonarQuery) -> Self {
+        SlidingWindow {
+            window: query.window,
+            aggregation: query.aggregation,
+            measurements: VecDeque::with_capacity(query.window + 1),
+            sum: 0,
+            extremes: VecDeque::new(),
+            pushed: 0,
+        }
+    }
+
+    /// Add the next measurement, returning the value of the window ending with
+    /// it once the window is full. Means are returned as the sum of the window,
+    /// which compares the same way between windows of the same size.
+    ///
+    /// Sums are checked, and fail if a window's sum, or the sum of the
+    /// measurements it shares with the window before, doesn't fit in an `i128`.
+    pub fn push(&mut self, n: T) -> Result<Option<i128>, Overflow> {
+        let index = self.pushed;
+        self.pushed += 1;
+        if matches!(self.aggregation, Aggregation::Sum | Aggregation::Mean) {
+            let mut sum = Some(self.sum);
+            if self.measurements.len() == self.window {
+                let oldest = self.measurements.pop_front().unwrap();
+                sum = sum.and_then(|sum| sum.checked_sub(oldest.into()));
+            }
+            self.sum = sum
+                .and_then(|sum| sum.checked_add(n.into()))
+                .ok_or(Overflow { index })?;
+        } else if self.measurements.len() == self.window {
+            self.measurements.pop_front();
+        }
+        self.measurements.push_back(n);
+        if matches!(self.aggregation, Aggregation::Min | Aggregation::Max) {
+            let replaces = |extreme: T| match self.aggregation {
+                Aggregation::Min => n <= extreme,
+                _ => n >= extreme,
+            };
+            while self.extremes.back().is_some_and(|&(_, m)| replaces(m)) {
+                self.extremes.pop_back();
+            }
+            self.extremes.push_back((index, n));
+            while self.extremes[0].0 + self.window <= index {
+                self.extremes.pop_front();
+            }
+        }
+        if self.measurements.len() < self.window {
+            return Ok(None);
+        }
+        Ok(Some(match self.aggregation {
+            Aggregation::Sum | Aggregation::Mean => self.sum,
+            Aggregation::Min | Aggregation::Max => self.extremes[0].1.into(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    const EXAMPLE: [i32; 10] = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+
+    #[test]
+    fn example_increases() {
+        let query = SonarQuery::new(1, Aggregation::Sum, Comparison::Increase);
+        assert_eq!(query.count(&EXAMPLE).unwrap(), 7);
+        let query = SonarQuery::new(3, Aggregation::Sum, Comparison::Increase);
+        assert_eq!(query.count(&EXAMPLE).unwrap(), 5);
+    }
+
+    #[test]
+    fn example_decreases() {
+        let query = SonarQuery::new(1, Aggregation::Sum, Comparison::Decrease);
+        assert_eq!(query.count(&EXAMPLE).unwrap(), 2);
+    }
+
+    #[test]
+    fn example_changes_beyond_threshold() {
+        // Window means are 202.3, 206, 206, 205.7, 215.7, 238.7, 256.3, 264.
+        let query = SonarQuery::new(3, Aggregation::Mean, Comparison::ChangeBeyond(5));
+        assert_eq!(query.count(&EXAMPLE).unwrap(), 4);
+    }
+
+    #[test]
+    fn example_extremes() {
+        // Window minimums are 199, 200, 200, 200, 200, 207, 240, 260.
+        let query = SonarQuery::new(3, Aggregation::Min, Comparison::Increase);
+        assert_eq!(query.count(&EXAMPLE).unwrap(), 4);
+        // Window maximums are 208, 210, 210, 210, 240, 269, 269, 269.
+        let query = SonarQuery::new(3, Aggregation::Max, Comparison::Increase);
+        assert_eq!(query.count(&EXAMPLE).unwrap(), 3);
+    }
+
+    #[test]
+    fn wide_depths() {
+        let numbers = [i64::MAX, i64::MAX, i64::MAX, i64::MIN];
+        let query = SonarQuery::new(3, Aggregation::Sum, Comparison::Decrease);
+        assert_eq!(query.count(&numbers), Ok(1));
+        let numbers = [i128::MAX, -1, i128::MAX, 1];
+        let query = SonarQuery::new(2, Aggregation::Sum, Comparison::Increase);
+        assert_eq!(query.count(&numbers), Err(Overflow { index: 3 }));
+        let query = SonarQuery::new(1, Aggregation::Min, Comparison::ChangeBeyond(0));
+        assert_eq!(query.count(&[0, i128::MIN]), Ok(1));
+        assert_eq!(query.count(&[-1, i128::MAX, i128::MIN]), Ok(2));
+        let query = SonarQuery::new(1, Aggregation::Min, Comparison::ChangeBeyond(-1));
+        assert_eq!(query.count(&[5, 5]), Ok(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "window size must be at least 1")]
+    fn rejects_empty_window() {
+        SonarQuery::new(0, Aggregation::Sum, Comparison::Increase);
+    }
+
+    fn aggregation() -> impl Strategy<Value = Aggregation> {
+        prop_oneof![
+            Just(Aggregation::Sum),
+            Just(Aggregation::Mean),
+            Just(Aggregation::Min),
+            Just(Aggregation::Max),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn sliding_window_matches_windows(
+            numbers in vec(-1000..1000, 0..100),
+            window in 1..10usize,
+            aggregation in aggregation(),
+        ) {
+            let query = SonarQuery::new(window, aggregation, Comparison::Increase);
+            let mut sliding_window = SlidingWindow::new(&query);
+            let values: Vec<i128> = numbers.iter().filter_map(|&n| sliding_window.push(n).unwrap()).collect();
+            let expected: Vec<i128> = numbers
+                .windows(window)
+                .map(|w| match aggregation {
+                    Aggregation::Sum | Aggregation::Mean => w.iter().map(|&n| n as i128).sum(),
+                    Aggregation::Min => *w.iter().min().unwrap() as i128,
+                    Aggregation::Max => *w.iter().max().unwrap() as i128,
+                })
+                .collect();
+            prop_assert_eq!(values, expected);
+        }
+
+        #[test]
+        fn sum_increases_match_parts(numbers in vec(-1000..1000, 0..100)) {
+            let query = SonarQuery::new(1, Aggregation::Sum, Comparison::Increase);
+            prop_assert_eq!(query.count(&numbers).unwrap(), crate::part1(&numbers));
+            let query = SonarQuery::new(3, Aggregation::Sum, Comparison::Increase);
+            prop_assert_eq!(query.count(&numbers).unwrap(), crate::part2(&numbers));
+        }
+    }
+}
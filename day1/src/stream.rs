@@ -0,0 +1,127 @@
+//! Sonar analysis over input that is too large to hold in memory, such as a
+//! multi-gigabyte log or a live pipe, reading one measurement at a time.
+
+use crate::depth::{self, Depth};
+use crate::sonar::{Aggregation, Comparison, SonarCounter, SonarQuery};
+use common::{Line, ParseError, ReadError};
+use std::io::BufRead;
+use std::marker::PhantomData;
+
+/// Iterates over the depth measurements read from `reader`, one per line, as
+/// integers of type `T`, reusing a single line buffer.
+pub struct Measurements<R, T> {
+    reader: R,
+    buffer: String,
+    line_number: usize,
+    depth: PhantomData<T>,
+}
+
+impl<R: BufRead, T: Depth> Measurements<R, T> {
+    pub fn new(reader: R) -> Self {
+        Measurements {
+            reader,
+            buffer: String::new(),
+            line_number: 0,
+            depth: PhantomData,
+        }
+    }
+
+    /// Line number of the measurement last returned.
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+}
+
+impl<R: BufRead, T: Depth> Iterator for Measurements<R, T> {
+    type Item = Result<T, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.buffer.clear();
+        match self.reader.read_line(&mut self.buffer) {
+            Ok(0) => None,
+            Ok(_) => {
+                self.line_number += 1;
+                let text = self.buffer.strip_suffix('\n').unwrap_or(&self.buffer);
+                let text = text.strip_suffix('\r').unwrap_or(text);
+                let line = Line {
+                    number: self.line_number,
+                    text,
+                };
+                Some(depth::parse_depth(&line).map_err(ReadError::from))
+            }
+            Err(e) => Some(Err(e.into())),
+        }
+    }
+}
+
+/// Answer every query in a single pass over the measurements read from
+/// `reader`, using memory proportional to the largest window rather than the
+/// length of the input. Measurements are read as integers of type `T`.
+pub fn count_stream<T: Depth>(
+    queries: &[SonarQuery],
+    reader: impl BufRead,
+) -> Result<Vec<usize>, ReadError> {
+    let mut counters: Vec<SonarCounter<T>> = queries.iter().map(SonarCounter::new).collect();
+    let mut measurements = Measurements::new(reader);
+    while let Some(n) = measurements.next() {
+        let n = n?;
+        for counter in &mut counters {
+            counter.push(n).map_err(|_| {
+                let expected = "a depth measurement that keeps window sums within i128";
+                ParseError::new(measurements.line_number(), 1, expected, &n.to_string())
+            })?;
+        }
+    }
+    Ok(counters.iter().map(SonarCounter::count).collect())
+}
+
+/// The queries answered by part 1 and part 2 of the puzzle.
+pub fn parts() -> [SonarQuery; 2] {
+    [
+        SonarQuery::new(1, Aggregation::Sum, Comparison::Increase),
+        SonarQuery::new(3, Aggregation::Sum, Comparison::Increase),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("example.txt");
+
+    #[test]
+    fn example_parts() {
+        assert_eq!(
+            count_stream::<i32>(&parts(), EXAMPLE.as_bytes()).unwrap(),
+            [7, 5]
+        );
+    }
+
+    #[test]
+    fn crlf_line_endings() {
+        let input = EXAMPLE.replace('\n', "\r\n");
+        assert_eq!(
+            count_stream::<i32>(&parts(), input.as_bytes()).unwrap(),
+            [7, 5]
+        );
+    }
+
+    #[test]
+    fn error_has_line_number() {
+        let error = count_stream::<i32>(&parts(), "199\n200\n2O8\n".as_bytes()).unwrap_err();
+        match error {
+            ReadError::Parse(e) => assert_eq!((e.line, e.column, e.found.as_str()), (3, 1, "2O8")),
+            ReadError::Io(e) => panic!("unexpected I/O error: {}", e),
+        }
+    }
+
+    #[test]
+    fn overflow_has_line_number() {
+        let input = format!("{}\n1\n", i128::MAX);
+        let error = count_stream::<i128>(&parts(), input.as_bytes()).unwrap_err();
+        match error {
+            ReadError::Parse(e) => assert_eq!((e.line, e.found.as_str()), (2, "1")),
+            ReadError::Io(e) => panic!("unexpected I/O error: {}", e),
+        }
+    }
+}
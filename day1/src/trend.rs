@@ -0,0 +1,287 @@
+//! A report on the shape of the depth series: where it rises, falls and stays
+//! level, and its sharpest changes.
+
+use crate::depth::Depth;
+use std::fmt;
+
+/// Direction of the change from one measurement to the next.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Trend {
+    Increasing,
+    Decreasing,
+    Flat,
+}
+
+impl Trend {
+    pub const ALL: [Trend; 3] = [Trend::Increasing, Trend::Decreasing, Trend::Flat];
+
+    pub fn of<T: Ord>(a: T, b: T) -> Self {
+        match b.cmp(&a) {
+            std::cmp::Ordering::Greater => Trend::Increasing,
+            std::cmp::Ordering::Less => Trend::Decreasing,
+            std::cmp::Ordering::Equal => Trend::Flat,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Trend::Increasing => "increasing",
+            Trend::Decreasing => "decreasing",
+            Trend::Flat => "flat",
+        }
+    }
+}
+
+/// The longest stretch of measurements over which every change has the same
+/// trend. Positions are indices into the measurements, so measurement `i` is
+/// on line `i + 1` of the input. Neighbouring runs share the measurement where
+/// one ends and the next begins.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Run {
+    pub trend: Trend,
+    pub first: usize,
+    pub last: usize,
+}
+
+impl Run {
+    /// Number of changes in the run, one fewer than its measurements.
+    pub fn steps(&self) -> usize {
+        self.last - self.first
+    }
+}
+
+/// The change from measurement `from` to the one after it. Only its size is
+/// kept, as whether it is a jump or a drop says which way it goes, and the size
+/// of a change between two `i128` depths can need all 128 bits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Change {
+    pub from: usize,
+    pub size: u128,
+}
+
+/// The depth series split into runs, along with its largest changes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TrendReport<T> {
+    pub measurements: usize,
+    pub runs: Vec<Run>,
+    /// Largest increase, if the depth ever increases.
+    pub largest_jump: Option<Change>,
+    /// Largest decrease, if the depth ever decreases.
+    pub largest_drop: Option<Change>,
+    // Depth of each plateau, in the same order as `plateaus`.
+    plateau_depths: Vec<T>,
+}
+
+impl<T: Depth> TrendReport<T> {
+    /// Analyse the series with the same comparison of neighbouring measurements
+    /// that `part1` makes.
+    pub fn new(numbers: &[T]) -> Self {
+        let mut runs: Vec<Run> = Vec::new();
+        let mut largest_jump: Option<Change> = None;
+        let mut largest_drop: Option<Change> = None;
+        for (i, (a, b)) in crate::pairs(numbers).enumerate() {
+            let trend = Trend::of(a, b);
+            match runs.last_mut() {
+                Some(run) if run.trend == trend => run.last = i + 1,
+                _ => runs.push(Run {
+                    trend,
+                    first: i,
+                    last: i + 1,
+                }),
+            }
+            let change = Change {
+                from: i,
+                size: a.into().abs_diff(b.into()),
+            };
+            let largest = match trend {
+                Trend::Increasing => &mut largest_jump,
+                Trend::Decreasing => &mut largest_drop,
+                Trend::Flat => continue,
+            };
+            if change.size > largest.map_or(0, |c| c.size) {
+                *largest = Some(change);
+            }
+        }
+        let plateau_depths = runs
+            .iter()
+            .filter(|run| run.trend == Trend::Flat)
+            .map(|run| numbers[run.first])
+            .collect();
+        TrendReport {
+            measurements: numbers.len(),
+            runs,
+            largest_jump,
+            largest_drop,
+            plateau_depths,
+        }
+    }
+
+    /// The earliest of the longest runs with the given trend.
+    pub fn longest(&self, trend: Trend) -> Option<Run> {
+        self.runs
+            .iter()
+            .filter(|run| run.trend == trend)
+            .fold(None, |longest: Option<&Run>, run| match longest {
+                Some(longest) if longest.steps() >= run.steps() => Some(longest),
+                _ => Some(run),
+            })
+            .copied()
+    }
+
+    /// Runs where the depth stays level, along with that depth.
+    pub fn plateaus(&self) -> impl Iterator<Item = (Run, T)> + '_ {
+        self.runs
+            .iter()
+            .filter(|run| run.trend == Trend::Flat)
+            .copied()
+            .zip(self.plateau_depths.iter().copied())
+    }
+}
+
+impl<T: Depth> fmt::Display for TrendReport<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Measurements: {}", self.measurements)?;
+        let counts: Vec<String> = Trend::ALL
+            .iter()
+            .map(|&trend| {
+                let count = self.runs.iter().filter(|run| run.trend == trend).count();
+                format!("{} {}", count, trend.name())
+            })
+            .collect();
+        writeln!(f, "Runs: {}", counts.join(", "))?;
+        for trend in Trend::ALL {
+            if let Some(run) = self.longest(trend) {
+                writeln!(
+                    f,
+                    "Longest {} run: {} step{}, lines {}-{}",
+                    trend.name(),
+                    run.steps(),
+                    if run.steps() == 1 { "" } else { "s" },
+                    run.first + 1,
+                    run.last + 1
+                )?;
+            }
+        }
+        let changes = [
+            ("jump", '+', self.largest_jump),
+            ("drop", '-', self.largest_drop),
+        ];
+        for (name, sign, change) in changes {
+            if let Some(change) = change {
+                writeln!(
+                    f,
+                    "Largest {}: {}{}, lines {}-{}",
+                    name,
+                    sign,
+                    change.size,
+                    change.from + 1,
+                    change.from + 2
+                )?;
+            }
+        }
+        if self.plateau_depths.is_empty() {
+            writeln!(f, "Plateaus: none")?;
+        }
+        for (run, depth) in self.plateaus() {
+            writeln!(
+                f,
+                "Plateau: {} measurements at depth {}, lines {}-{}",
+                run.steps() + 1,
+                depth,
+                run.first + 1,
+                run.last + 1
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    const EXAMPLE: [i32; 10] = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+
+    #[test]
+    fn example_runs() {
+        let report = TrendReport::new(&EXAMPLE);
+        let runs: Vec<(Trend, usize, usize)> = report
+            .runs
+            .iter()
+            .map(|run| (run.trend, run.first, run.last))
+            .collect();
+        assert_eq!(
+            runs,
+            [
+                (Trend::Increasing, 0, 3),
+                (Trend::Decreasing, 3, 4),
+                (Trend::Increasing, 4, 7),
+                (Trend::Decreasing, 7, 8),
+                (Trend::Increasing, 8, 9),
+            ]
+        );
+        assert_eq!(
+            report.longest(Trend::Increasing),
+            Some(Run {
+                trend: Trend::Increasing,
+                first: 0,
+                last: 3
+            })
+        );
+        assert_eq!(report.longest(Trend::Flat), None);
+    }
+
+    #[test]
+    fn example_largest_changes() {
+        let report = TrendReport::new(&EXAMPLE);
+        assert_eq!(report.largest_jump, Some(Change { from: 5, size: 33 }));
+        assert_eq!(report.largest_drop, Some(Change { from: 3, size: 10 }));
+    }
+
+    #[test]
+    fn plateaus() {
+        let report = TrendReport::new(&[3, 5, 5, 5, 4, 4, 6]);
+        let plateaus: Vec<(usize, usize, i32)> = report
+            .plateaus()
+            .map(|(run, depth)| (run.first, run.last, depth))
+            .collect();
+        assert_eq!(plateaus, [(1, 3, 5), (4, 5, 4)]);
+    }
+
+    #[test]
+    fn wide_depths() {
+        let report = TrendReport::new(&[i64::MIN, i64::MAX, i64::MAX]);
+        assert_eq!(
+            report.largest_jump,
+            Some(Change {
+                from: 0,
+                size: u64::MAX as u128
+            })
+        );
+        assert_eq!(report.plateaus().next().unwrap().1, i64::MAX);
+        let report = TrendReport::new(&[i128::MIN, i128::MAX, i128::MIN]);
+        assert_eq!(report.largest_jump.unwrap().size, u128::MAX);
+        assert_eq!(report.largest_drop.unwrap().size, u128::MAX);
+        assert!(report
+            .to_string()
+            .contains(&format!("Largest drop: -{}, lines 2-3", u128::MAX)));
+    }
+
+    proptest! {
+        #[test]
+        fn increasing_steps_match_part1(numbers in vec(-100..100, 0..200)) {
+            let report = TrendReport::new(&numbers);
+            let increases: usize = report
+                .runs
+                .iter()
+                .filter(|run| run.trend == Trend::Increasing)
+                .map(Run::steps)
+                .sum();
+            prop_assert_eq!(increases, crate::part1(&numbers));
+            let steps: usize = report.runs.iter().map(Run::steps).sum();
+            prop_assert_eq!(steps, numbers.len().saturating_sub(1));
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use crate::Day2;
+use common::{Generator, Rng};
+use std::io::{self, Write};
+
+// Commands never take the submarine above the surface under the part 1
+// interpretation.
+impl Generator for Day2 {
+    fn generate(rng: &mut Rng, size: usize, out: &mut dyn Write) -> io::Result<()> {
+        let mut depth = 0;
+        for _ in 0..size {
+            let amount = rng.range(1, 9);
+            match rng.below(10) {
+                0..=4 => writeln!(out, "forward {}", amount)?,
+                5..=7 => {
+                    depth += amount;
+                    writeln!(out, "down {}", amount)?;
+                }
+                _ if amount > depth => {
+                    depth += amount;
+                    writeln!(out, "down {}", amount)?;
+                }
+                _ => {
+                    depth -= amount;
+                    writeln!(out, "up {}", amount)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,566 @@
+//! Day 2: Dive!
+
+mod generate;
+pub mod mission;
+pub mod navigation;
+pub mod plan;
+pub mod program;
+pub mod reference;
+pub mod repl;
+pub mod stream;
+pub mod trajectory;
+pub mod transform;
+
+use common::{Line, ParseError, Solution};
+use program::Program;
+use std::fmt;
+use transform::{checked_execute_program, reduce_program, ComposableState};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Forward,
+    /// Forward, but in reverse.
+    Back,
+    Down,
+    Up,
+    /// Set the aim to the amount, where the interpretation has one.
+    Aim,
+    /// Change the heading by the amount in degrees, where the interpretation
+    /// has one.
+    TurnLeft,
+    TurnRight,
+    /// Change the pitch by the amount in degrees, where the interpretation has
+    /// one.
+    PitchUp,
+    PitchDown,
+}
+
+impl Direction {
+    /// The word for the direction in the input, as read by `parse_direction`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Direction::Forward => "forward",
+            Direction::Back => "back",
+            Direction::Down => "down",
+            Direction::Up => "up",
+            Direction::Aim => "aim",
+            Direction::TurnLeft => "turn left",
+            Direction::TurnRight => "turn right",
+            Direction::PitchUp => "pitch up",
+            Direction::PitchDown => "pitch down",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Command {
+    pub direction: Direction,
+    pub amount: i32,
+}
+
+/// Commands are written as they appear in the input, such as `forward 5`.
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.direction.name(), self.amount)
+    }
+}
+
+/// Position of the submarine, where `vertical` is the depth. Positions are
+/// wider than command amounts, so that inputs of many millions of commands fit.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SubmarinePosition {
+    pub horizontal: i64,
+    pub vertical: i64,
+}
+
+impl SubmarinePosition {
+    /// The puzzle answer: horizontal position multiplied by depth.
+    pub fn product(self) -> i128 {
+        self.horizontal as i128 * self.vertical as i128
+    }
+}
+
+/// An interpretation of what the commands mean.
+pub trait SubmarineState: Sized {
+    fn get_position(self) -> SubmarinePosition;
+
+    /// Carry out a command. May panic if the result is beyond the range of the
+    /// state, which `Day2::parse` rules out for the puzzle's interpretations.
+    fn execute(self, c: &Command) -> Self;
+
+    /// Carry out a command, or return `None` if the result is beyond the range
+    /// of the state. Interpretations that can overflow should override this,
+    /// as the default assumes `execute` can't.
+    fn try_execute(self, c: &Command) -> Option<Self> {
+        Some(self.execute(c))
+    }
+
+    /// The aim, for interpretations that have one.
+    fn get_aim(self) -> Option<i64> {
+        None
+    }
+}
+
+pub(crate) const OUT_OF_RANGE: &str = "takes the submarine beyond the range of its state";
+
+fn out_of_range_panic(c: &Command) -> ! {
+    panic!("'{}' {}", c, OUT_OF_RANGE)
+}
+
+/// A step of a mission, such as a command or a whole repeat block, that would
+/// take the submarine beyond the range of its state.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Overflow {
+    /// Line of the step in the mission.
+    pub line: usize,
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {} {}", self.line, OUT_OF_RANGE)
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+impl Overflow {
+    /// An error pointing at the step in `input`, the text of the mission it
+    /// came from.
+    pub fn parse_error(&self, input: &str) -> ParseError {
+        let line = common::lines(input)
+            .nth(self.line - 1)
+            .expect("overflow should be on a line of the input");
+        out_of_range(&line)
+    }
+}
+
+/// An error saying that the command or block on `line` takes the submarine
+/// out of range.
+pub fn out_of_range(line: &Line) -> ParseError {
+    let text = line.text.split('#').next().unwrap().trim();
+    line.error(text, IN_RANGE)
+}
+
+const IN_RANGE: &str = "a command that keeps the submarine within range";
+
+/// Commands move the submarine directly.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Part1SubmarineState(pub SubmarinePosition);
+
+impl SubmarineState for Part1SubmarineState {
+    fn get_position(self) -> SubmarinePosition {
+        self.0
+    }
+
+    fn execute(self, c: &Command) -> Self {
+        self.try_execute(c).unwrap_or_else(|| out_of_range_panic(c))
+    }
+
+    fn try_execute(self, c: &Command) -> Option<Self> {
+        let amount = c.amount as i64;
+        let SubmarinePosition {
+            horizontal,
+            vertical,
+        } = self.0;
+        let position = match c.direction {
+            Direction::Forward => SubmarinePosition {
+                horizontal: horizontal.checked_add(amount)?,
+                vertical,
+            },
+            Direction::Back => SubmarinePosition {
+                horizontal: horizontal.checked_sub(amount)?,
+                vertical,
+            },
+            Direction::Down => SubmarinePosition {
+                horizontal,
+                vertical: vertical.checked_add(amount)?,
+            },
+            Direction::Up => SubmarinePosition {
+                horizontal,
+                vertical: vertical.checked_sub(amount)?,
+            },
+            Direction::Aim
+            | Direction::TurnLeft
+            | Direction::TurnRight
+            | Direction::PitchUp
+            | Direction::PitchDown => self.0,
+        };
+        Some(Self(position))
+    }
+}
+
+/// Up and down commands change the aim, and forward commands move the submarine
+/// along it.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Part2SubmarineState {
+    pub position: SubmarinePosition,
+    pub aim: i64,
+}
+
+impl SubmarineState for Part2SubmarineState {
+    fn get_position(self) -> SubmarinePosition {
+        self.position
+    }
+
+    fn get_aim(self) -> Option<i64> {
+        Some(self.aim)
+    }
+
+    fn execute(self, c: &Command) -> Self {
+        self.try_execute(c).unwrap_or_else(|| out_of_range_panic(c))
+    }
+
+    fn try_execute(self, c: &Command) -> Option<Self> {
+        let amount = c.amount as i64;
+        let SubmarinePosition {
+            horizontal,
+            vertical,
+        } = self.position;
+        Some(match c.direction {
+            Direction::Forward => Self {
+                position: SubmarinePosition {
+                    horizontal: horizontal.checked_add(amount)?,
+                    vertical: vertical.checked_add(self.aim.checked_mul(amount)?)?,
+                },
+                aim: self.aim,
+            },
+            Direction::Back => Self {
+                position: SubmarinePosition {
+                    horizontal: horizontal.checked_sub(amount)?,
+                    vertical: vertical.checked_sub(self.aim.checked_mul(amount)?)?,
+                },
+                aim: self.aim,
+            },
+            Direction::Down => Self {
+                position: self.position,
+                aim: self.aim.checked_add(amount)?,
+            },
+            Direction::Up => Self {
+                position: self.position,
+                aim: self.aim.checked_sub(amount)?,
+            },
+            Direction::Aim => Self {
+                position: self.position,
+                aim: amount,
+            },
+            Direction::TurnLeft
+            | Direction::TurnRight
+            | Direction::PitchUp
+            | Direction::PitchDown => self,
+        })
+    }
+}
+
+pub fn parse_direction(input_direction: &str) -> Option<Direction> {
+    match input_direction {
+        "forward" => Some(Direction::Forward),
+        "back" => Some(Direction::Back),
+        "down" => Some(Direction::Down),
+        "up" => Some(Direction::Up),
+        "aim" => Some(Direction::Aim),
+        "turn left" => Some(Direction::TurnLeft),
+        "turn right" => Some(Direction::TurnRight),
+        "pitch up" => Some(Direction::PitchUp),
+        "pitch down" => Some(Direction::PitchDown),
+        _ => None,
+    }
+}
+
+// Split off the first word of `text`, returning it and the rest of the text
+// after any whitespace.
+fn split_word(text: &str) -> (&str, &str) {
+    let end = text
+        .find(|c: char| c.is_ascii_whitespace())
+        .unwrap_or(text.len());
+    let (word, rest) = text.split_at(end);
+    (word, rest.trim_start())
+}
+
+/// Parse a command such as `forward 5`.
+pub fn parse_input_line(line: &Line) -> Result<Command, ParseError> {
+    parse_command(line, line.text)
+}
+
+/// Parse a command that makes up `text`, which must be a slice of `line`.
+/// Whitespace may separate the direction and amount, and may follow the
+/// amount.
+pub fn parse_command(line: &Line, text: &str) -> Result<Command, ParseError> {
+    let (word, rest) = split_word(text);
+    let (direction, rest) = match word {
+        "turn" | "pitch" => {
+            let (side, rest) = split_word(rest);
+            let direction = match (word, side) {
+                ("turn", "left") => Direction::TurnLeft,
+                ("turn", "right") => Direction::TurnRight,
+                ("pitch", "up") => Direction::PitchUp,
+                ("pitch", "down") => Direction::PitchDown,
+                ("turn", _) => return Err(line.error(side, "left or right")),
+                _ => return Err(line.error(side, "up or down")),
+            };
+            (direction, rest)
+        }
+        _ => {
+            let direction = parse_direction(word)
+                .ok_or_else(|| line.error(word, "forward, back, down, up, aim, turn or pitch"))?;
+            (direction, rest)
+        }
+    };
+    let (amount, rest) = split_word(rest);
+    let amount = parse_amount(line, amount)?;
+    if !rest.is_empty() {
+        return Err(line.error(rest, "the end of the line"));
+    }
+    Ok(Command { direction, amount })
+}
+
+// Parse an optionally negative decimal amount, pointing any error at the first
+// character that isn't a digit.
+fn parse_amount(line: &Line, field: &str) -> Result<i32, ParseError> {
+    let bytes = field.as_bytes();
+    let negative = bytes.first() == Some(&b'-');
+    let digits = &bytes[negative as usize..];
+    if digits.is_empty() {
+        return Err(line.error(field, "an amount"));
+    }
+    let mut amount: i32 = 0;
+    for (i, &b) in digits.iter().enumerate() {
+        if !b.is_ascii_digit() {
+            return Err(line.error(&field[negative as usize + i..], "a digit"));
+        }
+        // Accumulate negative amounts downwards so that i32::MIN fits.
+        let digit = (b - b'0') as i32;
+        amount = amount
+            .checked_mul(10)
+            .and_then(|a| {
+                if negative {
+                    a.checked_sub(digit)
+                } else {
+                    a.checked_add(digit)
+                }
+            })
+            .ok_or_else(|| line.error(field, "an amount that fits in 32 bits"))?;
+    }
+    Ok(amount)
+}
+
+/// Parse one command per line.
+pub fn parse_input(input: &str) -> Result<Vec<Command>, ParseError> {
+    common::lines(input)
+        .map(|line| parse_input_line(&line))
+        .collect()
+}
+
+/// Calculate the product of the horizontal position and depth resulting from
+/// following the commands.
+pub fn get_solution(initial_state: impl SubmarineState, commands: &[Command]) -> i128 {
+    commands
+        .iter()
+        .fold(initial_state, |s, c| s.execute(c))
+        .get_position()
+        .product()
+}
+
+pub fn part1(commands: &[Command]) -> i128 {
+    get_solution(Part1SubmarineState::default(), commands)
+}
+
+pub fn part2(commands: &[Command]) -> i128 {
+    get_solution(Part2SubmarineState::default(), commands)
+}
+
+pub struct Day2;
+
+// The input is read as a program, so that mission scripts can be solved too.
+impl Solution for Day2 {
+    const DAY: u32 = 2;
+    type Input = Program;
+    type Answer = i128;
+
+    // Missions that take either interpretation out of range are rejected
+    // here, so that the parts can't overflow. Parsing is the only step that can
+    // fail, but the check composes transforms rather than running the mission,
+    // so repeat blocks cost time that grows only with the log of their count.
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let program = program::parse_program(input)?;
+        checked_execute_program(Part1SubmarineState::default(), &program)
+            .and(checked_execute_program(
+                Part2SubmarineState::default(),
+                &program,
+            ))
+            .map_err(|overflow| overflow.parse_error(input))?;
+        Ok(program)
+    }
+
+    // The parts reduce the mission to a single transform rather than running
+    // it, so repeat blocks don't have to be expanded. `parse` has ruled out
+    // overflow, so the wrapping transforms give the exact result.
+    fn part1(program: &Self::Input) -> Self::Answer {
+        Part1SubmarineState::default()
+            .apply(&reduce_program::<Part1SubmarineState>(program))
+            .get_position()
+            .product()
+    }
+
+    fn part2(program: &Self::Input) -> Self::Answer {
+        Part2SubmarineState::default()
+            .apply(&reduce_program::<Part2SubmarineState>(program))
+            .get_position()
+            .product()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{Generator, Rng};
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    const EXAMPLE: &str = include_str!("example.txt");
+
+    fn command() -> impl Strategy<Value = Command> {
+        (0..5usize, -10..10).prop_map(|(d, amount)| Command {
+            direction: [
+                Direction::Forward,
+                Direction::Back,
+                Direction::Down,
+                Direction::Up,
+                Direction::Aim,
+            ]
+            .into_iter()
+            .nth(d)
+            .unwrap(),
+            amount,
+        })
+    }
+
+    #[test]
+    fn part1_example() {
+        let input = Day2::parse(EXAMPLE).unwrap();
+        assert_eq!(Day2::part1(&input), 150);
+    }
+
+    #[test]
+    fn part2_example() {
+        let input = Day2::parse(EXAMPLE).unwrap();
+        assert_eq!(Day2::part2(&input), 900);
+    }
+
+    #[test]
+    fn generated_input_parses() {
+        let mut text = Vec::new();
+        Day2::generate(&mut Rng::new(1), 1000, &mut text).unwrap();
+        Day2::parse(std::str::from_utf8(&text).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn solves_large_generated_input() {
+        let mut text = Vec::new();
+        Day2::generate(&mut Rng::new(1), 1_000_000, &mut text).unwrap();
+        let text = std::str::from_utf8(&text).unwrap();
+        let program = Day2::parse(text).unwrap();
+        let commands = parse_input(text).unwrap();
+        let (part1, part2) = (Day2::part1(&program), Day2::part2(&program));
+        assert_eq!(part1, reference::part1(&commands));
+        assert_eq!(part2, reference::part2(&commands));
+        // Both answers are far beyond what would fit in 32 bits.
+        assert!(part1.min(part2) > i32::MAX as i128);
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = parse_input("forward 5\nsideways 2\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+        assert_eq!(
+            error.expected,
+            "forward, back, down, up, aim, turn or pitch"
+        );
+        let error = parse_input("turn around 180\n").unwrap_err();
+        assert_eq!(
+            (error.column, error.expected.as_str()),
+            (6, "left or right")
+        );
+        assert_eq!(
+            parse_input("pitch  down 10\n").unwrap(),
+            [Command {
+                direction: Direction::PitchDown,
+                amount: 10
+            }]
+        );
+        let error = parse_input("down 1x\n").unwrap_err();
+        assert_eq!((error.column, error.expected.as_str()), (7, "a digit"));
+        let error = parse_input("up\n").unwrap_err();
+        assert_eq!((error.column, error.expected.as_str()), (3, "an amount"));
+        let error = parse_input("up -\n").unwrap_err();
+        assert_eq!((error.column, error.expected.as_str()), (4, "an amount"));
+        let error = parse_input("up 1 2\n").unwrap_err();
+        assert_eq!(
+            (error.column, error.expected.as_str()),
+            (6, "the end of the line")
+        );
+        let error = parse_input("up 2147483648\n").unwrap_err();
+        assert_eq!(error.expected, "an amount that fits in 32 bits");
+        assert_eq!(
+            parse_input("aim\t-2147483648 \n").unwrap(),
+            [Command {
+                direction: Direction::Aim,
+                amount: i32::MIN
+            }]
+        );
+    }
+
+    #[test]
+    fn solves_huge_repeat_counts() {
+        let program = Day2::parse("repeat 1000000000 {\n  forward 1\n  down 1\n}\n").unwrap();
+        let n: i128 = 1_000_000_000;
+        assert_eq!(Day2::part1(&program), n * n);
+        assert_eq!(Day2::part2(&program), n * (n * (n - 1) / 2));
+    }
+
+    #[test]
+    fn rejects_overflow() {
+        let error = Day2::parse("aim 2147483647\nrepeat 3 {\n  forward 2147483647 # too far\n}\n")
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "2:1: expected a command that keeps the submarine within range, found 'repeat 3 {'"
+        );
+        let error = Day2::parse("repeat 2 {\n  down 5\n  forward 2147483647\n  aim 2147483647\n  forward 2147483647\n  forward 2147483647\n}\n")
+            .unwrap_err();
+        assert_eq!((error.line, error.column), (6, 3));
+        // Huge repeat counts are checked without running every repetition.
+        let nested = "repeat 4294967295 {\n  repeat 4294967295 {\n    forward 0\n  }\n}\n";
+        let program = Day2::parse(nested).unwrap();
+        assert_eq!((Day2::part1(&program), Day2::part2(&program)), (0, 0));
+        let error = Day2::parse(&nested.replace("forward 0", "forward 1")).unwrap_err();
+        assert_eq!(error.line, 1);
+        let state = Part2SubmarineState {
+            aim: i64::MAX,
+            ..Default::default()
+        };
+        let down = Command {
+            direction: Direction::Down,
+            amount: 1,
+        };
+        assert_eq!(state.try_execute(&down), None);
+    }
+
+    proptest! {
+        #[test]
+        fn parts_match_reference(commands in vec(command(), 0..100)) {
+            prop_assert_eq!(part1(&commands), reference::part1(&commands));
+            prop_assert_eq!(part2(&commands), reference::part2(&commands));
+        }
+
+        #[test]
+        fn program_matches_commands(commands in vec(command(), 0..100)) {
+            let text: String = commands
+                .iter()
+                .map(|c| format!("{}\n", c))
+                .collect();
+            let program = Day2::parse(&text).unwrap();
+            prop_assert_eq!(Day2::part1(&program), part1(&commands));
+            prop_assert_eq!(Day2::part2(&program), part2(&commands));
+        }
+    }
+}
@@ -1,137 +1,246 @@
-use regex::Regex;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-
-lazy_static::lazy_static! {
-    static ref RE: Regex = Regex::new(r"^(\w+) (\d+)$").unwrap();
-}
-
-enum Direction {
-    Forward,
-    Down,
-    Up,
+use day2::mission::{self, Clamp, Constraints};
+use day2::plan::{plan, MAX_ROUTE_COMMANDS};
+use day2::program::{self, Program};
+use day2::repl;
+use day2::stream;
+use day2::trajectory::{self, Trajectory};
+use day2::{Part1SubmarineState, Part2SubmarineState, SubmarinePosition};
+use std::fs;
+use std::io::{self, BufWriter, Write};
+use std::process::ExitCode;
+
+const DEFAULT_INPUT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/input.txt");
+
+const USAGE: &str = "\
+Usage: day2 [PATH]
+       day2 stream [PATH|-]
+       day2 trace [--part <1|2>] [--format <csv|svg>] [PATH]
+       day2 plan [--part <1|2>] <HORIZONTAL> <DEPTH>
+       day2 repl
+       day2 check [--part <1|2>] [--min-depth <N>] [--max-depth <N>] [--max-aim <N>]
+                  [--max-horizontal <N>] [--clamp] [PATH]";
+
+// Answer both parts while reading the commands one line at a time, from the
+// named file or from stdin if the path is missing or `-`.
+fn run_stream(path: Option<&str>) -> Result<(), String> {
+    let (name, reader) = common::open_input(path)?;
+    let answers = stream::solve_stream(reader).map_err(|e| e.with_file(name).to_string())?;
+    println!("Part 1: {}", answers[0]);
+    println!("Part 2: {}", answers[1]);
+    Ok(())
 }
 
-struct Command {
-    direction: Direction,
-    amount: i32,
-}
-
-#[derive(Default)]
-struct SubmarinePosition {
-    horizontal: i32,
-    vertical: i32,
-}
-
-trait SubmarineState {
-    fn get_position(self) -> SubmarinePosition;
-    fn execute(self, c: &Command) -> Self;
-}
-
-#[derive(Default)]
-struct Part1SubmarineState(SubmarinePosition);
-
-impl SubmarineState for Part1SubmarineState {
-    fn get_position(self) -> SubmarinePosition {
-        self.0
-    }
-
-    fn execute(self, c: &Command) -> Self {
-        match c.direction {
-            Direction::Forward => Self(SubmarinePosition {
-                horizontal: self.0.horizontal + c.amount,
-                vertical: self.0.vertical,
-            }),
-            Direction::Down => Self(SubmarinePosition {
-                horizontal: self.0.horizontal,
-                vertical: self.0.vertical + c.amount,
-            }),
-            Direction::Up => Self(SubmarinePosition {
-                horizontal: self.0.horizontal,
-                vertical: self.0.vertical - c.amount,
-            }),
+// Print every position the submarine passes through, under one or both
+// interpretations, as CSV or an SVG plot.
+fn run_trace(mut args: impl Iterator<Item = String>) -> Result<(), String> {
+    let mut parts = vec![1, 2];
+    let mut svg = false;
+    let mut path = None;
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("{} requires a value", arg));
+        match arg.as_str() {
+            "--part" => match value()?.as_str() {
+                "1" => parts = vec![1],
+                "2" => parts = vec![2],
+                s => return Err(format!("invalid part '{}'", s)),
+            },
+            "--format" => match value()?.as_str() {
+                "csv" => svg = false,
+                "svg" => svg = true,
+                s => return Err(format!("invalid format '{}'", s)),
+            },
+            _ if path.is_none() && !arg.starts_with('-') => path = Some(arg),
+            _ => return Err(format!("unexpected argument '{}'", arg)),
         }
     }
-}
-
-#[derive(Default)]
-struct Part2SubmarineState {
-    position: SubmarinePosition,
-    aim: i32,
-}
-
-impl SubmarineState for Part2SubmarineState {
-    fn get_position(self) -> SubmarinePosition {
-        self.position
+    let path = path.as_deref().unwrap_or(DEFAULT_INPUT);
+    let text = fs::read_to_string(path).map_err(|e| format!("failed to read {}: {}", path, e))?;
+    let program = program::parse_program(&text).map_err(|e| e.with_file(path).to_string())?;
+    let trajectories: Vec<(&str, Trajectory)> = parts
+        .iter()
+        .map(|part| match part {
+            1 => Trajectory::record(Part1SubmarineState::default(), &program).map(|t| ("part1", t)),
+            _ => Trajectory::record(Part2SubmarineState::default(), &program).map(|t| ("part2", t)),
+        })
+        .collect::<Result<_, _>>()
+        .map_err(|overflow| overflow.parse_error(&text).with_file(path).to_string())?;
+    let trajectories: Vec<(&str, &Trajectory)> =
+        trajectories.iter().map(|(name, t)| (*name, t)).collect();
+    let mut out = BufWriter::new(io::stdout().lock());
+    if svg {
+        trajectory::write_svg(&trajectories, &mut out)
+    } else {
+        trajectory::write_csv(&trajectories, &mut out)
     }
+    .and_then(|_| out.flush())
+    .map_err(|e| format!("failed to write trajectory: {}", e))
+}
 
-    fn execute(self, c: &Command) -> Self {
-        match c.direction {
-            Direction::Forward => Self {
-                position: SubmarinePosition {
-                    horizontal: self.position.horizontal + c.amount,
-                    vertical: self.position.vertical + (self.aim * c.amount),
-                },
-                aim: self.aim,
+// Print a list of commands that reaches the target under one interpretation,
+// a shortest one for targets within a command's amount, as a script that can
+// be run.
+fn run_plan(mut args: impl Iterator<Item = String>) -> Result<(), String> {
+    let mut part = 1;
+    let mut numbers = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--part" => match args.next().as_deref() {
+                Some("1") => part = 1,
+                Some("2") => part = 2,
+                Some(s) => return Err(format!("invalid part '{}'", s)),
+                None => return Err("--part requires a value".to_string()),
             },
-            Direction::Down => Self {
-                position: self.position,
-                aim: self.aim + c.amount,
-            },
-            Direction::Up => Self {
-                position: self.position,
-                aim: self.aim - c.amount,
+            _ => match arg.parse::<i64>() {
+                Ok(n) if numbers.len() < 2 => numbers.push(n),
+                _ => return Err(format!("unexpected argument '{}'", arg)),
             },
         }
     }
-}
-
-fn parse_direction(input_direction: &str) -> Option<Direction> {
-    match input_direction {
-        "forward" => Some(Direction::Forward),
-        "down" => Some(Direction::Down),
-        "up" => Some(Direction::Up),
-        _ => None,
+    let target = match numbers[..] {
+        [horizontal, vertical] => SubmarinePosition {
+            horizontal,
+            vertical,
+        },
+        _ => return Err("plan requires a horizontal position and a depth".to_string()),
+    };
+    let commands = match part {
+        1 => plan::<Part1SubmarineState>(target),
+        _ => plan::<Part2SubmarineState>(target),
     }
-}
-
-fn parse_input_line(input_line: &str) -> Command {
-    let cap = RE.captures(input_line).unwrap();
-    Command {
-        direction: parse_direction(&cap[1]).unwrap(),
-        amount: cap[2].parse::<i32>().unwrap(),
+    .ok_or_else(|| {
+        format!(
+            "target is too far away for a route of at most {} commands",
+            MAX_ROUTE_COMMANDS
+        )
+    })?;
+    for command in commands {
+        println!("{}", command);
     }
+    Ok(())
 }
 
-fn read_input(file_path: &str) -> Vec<Command> {
-    let file = File::open(file_path).expect("Failed to read file");
-    let reader = BufReader::new(file);
-    reader
-        .lines()
-        .map(|line| parse_input_line(&line.unwrap()))
-        .collect()
-}
-
-/// Calculate the product of the horizontal position and depth resulting from
-/// following the commands.
-fn get_solution(initial_state: impl SubmarineState, commands: &[Command]) -> i32 {
-    let final_position = commands
-        .iter()
-        .fold(initial_state, |s, c| s.execute(c))
-        .get_position();
-    final_position.horizontal * final_position.vertical
+// Check a mission against the constraints under one interpretation, printing
+// where it ends. Returns whether it kept to them, or was held to them.
+fn check_part<S: Clamp + Default>(
+    name: &str,
+    program: &Program,
+    constraints: &Constraints,
+    clamp: bool,
+) -> bool {
+    let result = if clamp {
+        mission::run_clamped(S::default(), program, constraints)
+    } else {
+        mission::check(S::default(), program, constraints).map(|state| (state, vec![]))
+    };
+    let (state, violations) = match result {
+        Ok(result) => result,
+        Err(failure) => {
+            println!("{}: {}", name, failure);
+            return false;
+        }
+    };
+    for violation in &violations {
+        println!("{}: {} (clamped)", name, violation);
+    }
+    let position = state.get_position();
+    println!(
+        "{}: ends at horizontal {}, depth {}",
+        name, position.horizontal, position.vertical
+    );
+    true
 }
 
-fn part1(commands: &[Command]) -> i32 {
-    get_solution(Part1SubmarineState::default(), commands)
+// Check that a mission keeps the submarine within the given limits, or hold it
+// at the limits with --clamp.
+fn run_check(mut args: impl Iterator<Item = String>) -> Result<(), String> {
+    let mut parts = vec![1, 2];
+    let mut constraints = Constraints::default();
+    let mut clamp = false;
+    let mut path = None;
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("{} requires a value", arg));
+        let mut limit = || -> Result<Option<i64>, String> {
+            let value = value()?;
+            value
+                .parse()
+                .map(Some)
+                .map_err(|_| format!("invalid limit '{}'", value))
+        };
+        match arg.as_str() {
+            "--part" => match value()?.as_str() {
+                "1" => parts = vec![1],
+                "2" => parts = vec![2],
+                s => return Err(format!("invalid part '{}'", s)),
+            },
+            "--min-depth" => constraints.min_depth = limit()?,
+            "--max-depth" => constraints.max_depth = limit()?,
+            "--max-aim" => constraints.max_aim = limit()?,
+            "--max-horizontal" => constraints.max_horizontal = limit()?,
+            "--clamp" => clamp = true,
+            _ if path.is_none() && !arg.starts_with('-') => path = Some(arg),
+            _ => return Err(format!("unexpected argument '{}'", arg)),
+        }
+    }
+    constraints
+        .validate()
+        .map_err(|e| format!("invalid limits: {}", e))?;
+    let path = path.as_deref().unwrap_or(DEFAULT_INPUT);
+    let text = fs::read_to_string(path).map_err(|e| format!("failed to read {}: {}", path, e))?;
+    let program = program::parse_program(&text).map_err(|e| e.with_file(path).to_string())?;
+    let mut safe = true;
+    for part in parts {
+        safe &= match part {
+            1 => check_part::<Part1SubmarineState>("part 1", &program, &constraints, clamp),
+            _ => check_part::<Part2SubmarineState>("part 2", &program, &constraints, clamp),
+        };
+    }
+    if safe {
+        Ok(())
+    } else {
+        Err(format!("{} breaks the mission constraints", path))
+    }
 }
 
-fn part2(commands: &[Command]) -> i32 {
-    get_solution(Part2SubmarineState::default(), commands)
+// Drive the submarine one typed command at a time.
+fn run_repl(mut args: impl Iterator<Item = String>) -> Result<(), String> {
+    if let Some(arg) = args.next() {
+        return Err(format!("unexpected argument '{}'", arg));
+    }
+    repl::run(io::stdin().lock(), &mut io::stdout().lock())
+        .map_err(|e| format!("failed to run the console: {}", e))
 }
 
-fn main() {
-    let commands = read_input("day2/src/input.txt");
-    println!("Part 1: {}", part1(&commands));
-    println!("Part 2: {}", part2(&commands));
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1).peekable();
+    let result = match args.peek().map(String::as_str) {
+        Some("-h" | "--help") => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Some("stream") => run_stream(args.nth(1).as_deref()),
+        Some("trace") => {
+            args.next();
+            run_trace(args)
+        }
+        Some("plan") => {
+            args.next();
+            run_plan(args)
+        }
+        Some("check") => {
+            args.next();
+            run_check(args)
+        }
+        Some("repl") => {
+            args.next();
+            run_repl(args)
+        }
+        _ => return common::run::<day2::Day2>(DEFAULT_INPUT),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            ExitCode::FAILURE
+        }
+    }
 }
@@ -0,0 +1,451 @@
+//! Limits a mission has to keep the submarine within, such as staying below
+//! the surface, with a check that finds the first command to break one and a
+//! mode that holds the submarine at the limits instead.
+
+use crate::program::Program;
+use crate::OUT_OF_RANGE;
+use crate::{Command, Part1SubmarineState, Part2SubmarineState, SubmarinePosition, SubmarineState};
+use std::fmt;
+
+/// Limits on the submarine's state, each checked after every command. The aim
+/// and horizontal limits apply in both directions, so an aim of -5 breaks a
+/// maximum aim of 4.
+///
+/// The default keeps the submarine from going above the surface and sets no
+/// other limits. Constraints built any other way should be checked with
+/// `validate`, since limits that no state can meet are otherwise held to
+/// whichever bound comes last.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Constraints {
+    pub min_depth: Option<i64>,
+    pub max_depth: Option<i64>,
+    pub max_aim: Option<i64>,
+    pub max_horizontal: Option<i64>,
+}
+
+impl Default for Constraints {
+    fn default() -> Self {
+        Constraints {
+            min_depth: Some(0),
+            max_depth: None,
+            max_aim: None,
+            max_horizontal: None,
+        }
+    }
+}
+
+/// Which of the constraints was broken.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Limit {
+    MinDepth,
+    MaxDepth,
+    MaxAim,
+    MaxHorizontal,
+}
+
+/// A command that took the submarine past one of the limits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Violation {
+    /// Line of the command in the mission.
+    pub line: usize,
+    pub command: Command,
+    pub limit: Limit,
+    /// The value of the limit that was broken.
+    pub bound: i64,
+    /// The depth, aim or horizontal position the command led to.
+    pub value: i64,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (quantity, relation) = match self.limit {
+            Limit::MinDepth => ("depth", "above the minimum"),
+            Limit::MaxDepth => ("depth", "below the maximum"),
+            Limit::MaxAim => ("aim", "beyond the limit"),
+            Limit::MaxHorizontal => ("horizontal position", "beyond the limit"),
+        };
+        write!(
+            f,
+            "line {}: '{}' takes the {} to {}, {} of {}",
+            self.line, self.command, quantity, self.value, relation, self.bound
+        )
+    }
+}
+
+/// Why a mission failed its check.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Failure {
+    Violation(Violation),
+    /// The command on `line` took the submarine beyond the range of its state
+    /// before any limit was broken.
+    Overflow {
+        line: usize,
+        command: Command,
+    },
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Failure::Violation(violation) => violation.fmt(f),
+            Failure::Overflow { line, command } => {
+                write!(f, "line {}: '{}' {}", line, command, OUT_OF_RANGE)
+            }
+        }
+    }
+}
+
+impl From<Violation> for Failure {
+    fn from(violation: Violation) -> Self {
+        Failure::Violation(violation)
+    }
+}
+
+/// Why a set of constraints can't be met by any state.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InvalidConstraints {
+    /// An aim or horizontal limit, which applies in both directions, is below
+    /// zero.
+    Negative { limit: Limit, bound: i64 },
+    /// The minimum depth is below the maximum depth.
+    DepthRange { min_depth: i64, max_depth: i64 },
+}
+
+impl fmt::Display for InvalidConstraints {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InvalidConstraints::Negative { limit, bound } => {
+                let quantity = match limit {
+                    Limit::MaxAim => "aim",
+                    _ => "horizontal position",
+                };
+                write!(f, "the {} limit of {} is negative", quantity, bound)
+            }
+            InvalidConstraints::DepthRange {
+                min_depth,
+                max_depth,
+            } => write!(
+                f,
+                "the minimum depth of {} is below the maximum depth of {}",
+                min_depth, max_depth
+            ),
+        }
+    }
+}
+
+// Carry out the command on `line`, or report that it overflows.
+fn step<S: SubmarineState>(state: S, command: &Command, line: usize) -> Result<S, Failure> {
+    state.try_execute(command).ok_or(Failure::Overflow {
+        line,
+        command: *command,
+    })
+}
+
+// Whether `value` is further from zero than `bound` in either direction.
+fn beyond(value: i64, bound: i64) -> bool {
+    (value as i128).abs() > bound as i128
+}
+
+impl Constraints {
+    /// Check that some state meets every limit.
+    pub fn validate(&self) -> Result<(), InvalidConstraints> {
+        for (limit, bound) in [
+            (Limit::MaxAim, self.max_aim),
+            (Limit::MaxHorizontal, self.max_horizontal),
+        ] {
+            if let Some(bound) = bound.filter(|&bound| bound < 0) {
+                return Err(InvalidConstraints::Negative { limit, bound });
+            }
+        }
+        match (self.min_depth, self.max_depth) {
+            (Some(min_depth), Some(max_depth)) if min_depth > max_depth => {
+                Err(InvalidConstraints::DepthRange {
+                    min_depth,
+                    max_depth,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The first limit that `state` breaks, along with the limit's value and
+    /// the value that breaks it.
+    pub fn broken(&self, state: impl SubmarineState + Copy) -> Option<(Limit, i64, i64)> {
+        let SubmarinePosition {
+            horizontal,
+            vertical: depth,
+        } = state.get_position();
+        // Each limit, its value if it is set, the value it applies to if the
+        // interpretation has one, and whether that value breaks it.
+        type Check = (Limit, Option<i64>, Option<i64>, fn(i64, i64) -> bool);
+        let checks: [Check; 4] = [
+            (Limit::MinDepth, self.min_depth, Some(depth), |v, b| v < b),
+            (Limit::MaxDepth, self.max_depth, Some(depth), |v, b| v > b),
+            (Limit::MaxAim, self.max_aim, state.get_aim(), beyond),
+            (
+                Limit::MaxHorizontal,
+                self.max_horizontal,
+                Some(horizontal),
+                beyond,
+            ),
+        ];
+        checks
+            .into_iter()
+            .find_map(|(limit, bound, value, breaks)| {
+                let (bound, value) = (bound?, value?);
+                breaks(value, bound).then_some((limit, bound, value))
+            })
+    }
+
+    // Bring a value back within `min..=max`, where either may be missing.
+    fn hold(value: i64, min: Option<i64>, max: Option<i64>) -> i64 {
+        let value = min.map_or(value, |min| value.max(min));
+        max.map_or(value, |max| value.min(max))
+    }
+
+    fn hold_depth(&self, depth: i64) -> i64 {
+        Self::hold(depth, self.min_depth, self.max_depth)
+    }
+
+    fn hold_symmetric(value: i64, limit: Option<i64>) -> i64 {
+        Self::hold(value, limit.map(i64::saturating_neg), limit)
+    }
+}
+
+/// An interpretation whose state can be brought back within the constraints.
+pub trait Clamp: SubmarineState + Copy {
+    /// The nearest state that keeps to `constraints`.
+    fn clamp(self, constraints: &Constraints) -> Self;
+}
+
+impl Clamp for Part1SubmarineState {
+    fn clamp(self, constraints: &Constraints) -> Self {
+        Part1SubmarineState(SubmarinePosition {
+            horizontal: Constraints::hold_symmetric(self.0.horizontal, constraints.max_horizontal),
+            vertical: constraints.hold_depth(self.0.vertical),
+        })
+    }
+}
+
+impl Clamp for Part2SubmarineState {
+    fn clamp(self, constraints: &Constraints) -> Self {
+        Part2SubmarineState {
+            position: Part1SubmarineState(self.position).clamp(constraints).0,
+            aim: Constraints::hold_symmetric(self.aim, constraints.max_aim),
+        }
+    }
+}
+
+/// Run `program` from `state`, stopping at the first command that breaks one
+/// of the constraints or takes the submarine out of range. Returns the final
+/// state if none do.
+pub fn check<S: SubmarineState + Copy>(
+    state: S,
+    program: &Program,
+    constraints: &Constraints,
+) -> Result<S, Failure> {
+    let mut state = state;
+    program.try_for_each(|command, line| {
+        state = step(state, command, line)?;
+        match constraints.broken(state) {
+            Some((limit, bound, value)) => Err(Failure::Violation(Violation {
+                line,
+                command: *command,
+                limit,
+                bound,
+                value,
+            })),
+            None => Ok(()),
+        }
+    })?;
+    Ok(state)
+}
+
+/// Run `program` from `state`, bringing the submarine back within the
+/// constraints after any command that takes it past them. Returns the final
+/// state and every violation that had to be corrected, or the first command
+/// that takes the submarine out of range.
+pub fn run_clamped<S: Clamp>(
+    state: S,
+    program: &Program,
+    constraints: &Constraints,
+) -> Result<(S, Vec<Violation>), Failure> {
+    let mut state = state;
+    let mut violations = Vec::new();
+    program.try_for_each(|command, line| {
+        state = step(state, command, line)?;
+        if let Some((limit, bound, value)) = constraints.broken(state) {
+            violations.push(Violation {
+                line,
+                command: *command,
+                limit,
+                bound,
+                value,
+            });
+            state = state.clamp(constraints);
+        }
+        Ok::<_, Failure>(())
+    })?;
+    Ok((state, violations))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::program::parse_program;
+
+    const EXAMPLE: &str = include_str!("example.txt");
+
+    fn example() -> Program {
+        parse_program(EXAMPLE).unwrap()
+    }
+
+    #[test]
+    fn example_is_safe() {
+        let constraints = Constraints::default();
+        let part1 = check(Part1SubmarineState::default(), &example(), &constraints).unwrap();
+        assert_eq!(part1.get_position().product(), 150);
+        let part2 = check(Part2SubmarineState::default(), &example(), &constraints).unwrap();
+        assert_eq!(part2.get_position().product(), 900);
+    }
+
+    #[test]
+    fn first_violation() {
+        let program = parse_program("down 2\n# comment\nup 3\nup 4\n").unwrap();
+        let violation = check(
+            Part1SubmarineState::default(),
+            &program,
+            &Constraints::default(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            violation.to_string(),
+            "line 3: 'up 3' takes the depth to -1, above the minimum of 0"
+        );
+        let constraints = Constraints {
+            max_depth: Some(50),
+            max_aim: Some(8),
+            ..Constraints::default()
+        };
+        let violation =
+            check(Part2SubmarineState::default(), &example(), &constraints).unwrap_err();
+        assert_eq!(
+            violation.to_string(),
+            "line 5: 'down 8' takes the aim to 10, beyond the limit of 8"
+        );
+        let constraints = Constraints {
+            max_horizontal: Some(12),
+            ..Constraints::default()
+        };
+        let violation =
+            check(Part1SubmarineState::default(), &example(), &constraints).unwrap_err();
+        assert!(matches!(
+            violation,
+            Failure::Violation(Violation {
+                limit: Limit::MaxHorizontal,
+                value: 13,
+                ..
+            })
+        ));
+        // Part 1 has no aim, so there is nothing to limit.
+        let constraints = Constraints {
+            max_aim: Some(0),
+            ..Constraints::default()
+        };
+        assert!(check(Part1SubmarineState::default(), &example(), &constraints).is_ok());
+    }
+
+    #[test]
+    fn clamping() {
+        let constraints = Constraints {
+            max_depth: Some(50),
+            max_aim: Some(8),
+            ..Constraints::default()
+        };
+        let (state, violations) =
+            run_clamped(Part2SubmarineState::default(), &example(), &constraints).unwrap();
+        // The aim is held at 8, so the last move takes the depth to 56, which
+        // is held at 50.
+        assert_eq!(
+            state,
+            Part2SubmarineState {
+                position: SubmarinePosition {
+                    horizontal: 15,
+                    vertical: 50
+                },
+                aim: 8
+            }
+        );
+        let lines: Vec<(usize, Limit)> = violations.iter().map(|v| (v.line, v.limit)).collect();
+        assert_eq!(lines, [(5, Limit::MaxAim), (6, Limit::MaxDepth)]);
+        let program = parse_program("up 5\nback 3\n").unwrap();
+        let constraints = Constraints {
+            max_horizontal: Some(2),
+            ..Constraints::default()
+        };
+        let (state, violations) =
+            run_clamped(Part1SubmarineState::default(), &program, &constraints).unwrap();
+        assert_eq!(
+            state.0,
+            SubmarinePosition {
+                horizontal: -2,
+                vertical: 0
+            }
+        );
+        assert_eq!(violations.len(), 2);
+    }
+
+    #[test]
+    fn impossible_constraints() {
+        assert_eq!(Constraints::default().validate(), Ok(()));
+        let level = Constraints {
+            min_depth: Some(5),
+            max_depth: Some(5),
+            max_aim: Some(0),
+            max_horizontal: Some(0),
+        };
+        assert_eq!(level.validate(), Ok(()));
+        let invalid = Constraints {
+            max_aim: Some(-1),
+            ..level
+        };
+        assert_eq!(
+            invalid.validate().unwrap_err().to_string(),
+            "the aim limit of -1 is negative"
+        );
+        let invalid = Constraints {
+            max_horizontal: Some(i64::MIN),
+            ..level
+        };
+        assert_eq!(
+            invalid.validate(),
+            Err(InvalidConstraints::Negative {
+                limit: Limit::MaxHorizontal,
+                bound: i64::MIN
+            })
+        );
+        let invalid = Constraints {
+            max_depth: Some(4),
+            ..level
+        };
+        assert_eq!(
+            invalid.validate().unwrap_err().to_string(),
+            "the minimum depth of 5 is below the maximum depth of 4"
+        );
+    }
+
+    #[test]
+    fn overflow() {
+        let program =
+            parse_program("aim 2147483647\nrepeat 3 {\n  forward 2147483647\n}\n").unwrap();
+        let constraints = Constraints::default();
+        let failure = check(Part2SubmarineState::default(), &program, &constraints).unwrap_err();
+        assert_eq!(
+            failure.to_string(),
+            "line 3: 'forward 2147483647' takes the submarine beyond the range of its state"
+        );
+        let overflow =
+            run_clamped(Part2SubmarineState::default(), &program, &constraints).unwrap_err();
+        assert!(matches!(overflow, Failure::Overflow { line: 3, .. }));
+    }
+}
@@ -0,0 +1,149 @@
+//! A more realistic interpretation, where the submarine moves in three
+//! dimensions along its heading and pitch.
+
+use crate::{Command, Direction, SubmarinePosition, SubmarineState};
+
+/// The submarine moves forward and back along its heading, which starts along
+/// the x axis and turns towards y when turning left, and along its pitch, which
+/// takes it deeper when pitched down. Down and up change the depth directly,
+/// and aim sets the pitch. Angles are whole degrees, and the pitch is kept
+/// between straight up and straight down.
+///
+/// The position reported to `get_solution` is x as the horizontal position and
+/// the depth, each rounded to the nearest whole number.
+#[derive(Default, Clone, Copy, Debug, PartialEq)]
+pub struct Submarine3dState {
+    pub x: f64,
+    pub y: f64,
+    pub depth: f64,
+    /// Degrees anticlockwise from the x axis, from 0 to 359.
+    pub heading: i32,
+    /// Degrees below the horizontal, from -90 to 90.
+    pub pitch: i32,
+}
+
+// Cosine and sine of an angle in degrees, exact for right angles so that
+// moves along the axes stay whole.
+fn cos_sin(degrees: i32) -> (f64, f64) {
+    match degrees.rem_euclid(360) {
+        0 => (1.0, 0.0),
+        90 => (0.0, 1.0),
+        180 => (-1.0, 0.0),
+        270 => (0.0, -1.0),
+        d => {
+            let radians = (d as f64).to_radians();
+            (radians.cos(), radians.sin())
+        }
+    }
+}
+
+impl Submarine3dState {
+    fn turn(self, degrees: i32) -> Self {
+        Self {
+            heading: (self.heading as i64 + degrees as i64).rem_euclid(360) as i32,
+            ..self
+        }
+    }
+
+    fn pitch_to(self, degrees: i64) -> Self {
+        Self {
+            pitch: degrees.clamp(-90, 90) as i32,
+            ..self
+        }
+    }
+
+    fn advance(self, distance: f64) -> Self {
+        let (cos_heading, sin_heading) = cos_sin(self.heading);
+        let (cos_pitch, sin_pitch) = cos_sin(self.pitch);
+        Self {
+            x: self.x + distance * cos_pitch * cos_heading,
+            y: self.y + distance * cos_pitch * sin_heading,
+            depth: self.depth + distance * sin_pitch,
+            ..self
+        }
+    }
+}
+
+impl SubmarineState for Submarine3dState {
+    fn get_position(self) -> SubmarinePosition {
+        SubmarinePosition {
+            horizontal: self.x.round() as i64,
+            vertical: self.depth.round() as i64,
+        }
+    }
+
+    fn execute(self, c: &Command) -> Self {
+        let amount = c.amount as f64;
+        match c.direction {
+            Direction::Forward => self.advance(amount),
+            Direction::Back => self.advance(-amount),
+            Direction::Down => Self {
+                depth: self.depth + amount,
+                ..self
+            },
+            Direction::Up => Self {
+                depth: self.depth - amount,
+                ..self
+            },
+            Direction::Aim => self.pitch_to(c.amount as i64),
+            Direction::TurnLeft => self.turn(c.amount % 360),
+            Direction::TurnRight => self.turn(-(c.amount % 360)),
+            Direction::PitchUp => self.pitch_to(self.pitch as i64 - c.amount as i64),
+            Direction::PitchDown => self.pitch_to(self.pitch as i64 + c.amount as i64),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::program::parse_program;
+    use crate::{get_solution, parse_input};
+
+    const EXAMPLE: &str = include_str!("example.txt");
+
+    #[test]
+    fn example_matches_part1() {
+        // Without turns or pitch, the submarine moves just as in part 1.
+        let commands = parse_input(EXAMPLE).unwrap();
+        assert_eq!(get_solution(Submarine3dState::default(), &commands), 150);
+    }
+
+    #[test]
+    fn navigates_in_three_dimensions() {
+        let program = parse_program(
+            "\
+forward 10
+turn left 90
+forward 5
+pitch down 90
+forward 3
+pitch up 45
+turn right 450
+back 4
+",
+        )
+        .unwrap();
+        let state = program.execute(Submarine3dState::default());
+        assert_eq!((state.heading, state.pitch), (0, 45));
+        let half = 4.0 * std::f64::consts::FRAC_1_SQRT_2;
+        assert!((state.x - (10.0 - half)).abs() < 1e-9);
+        assert_eq!(state.y, 5.0);
+        assert!((state.depth - (3.0 - half)).abs() < 1e-9);
+        assert_eq!(
+            state.get_position(),
+            SubmarinePosition {
+                horizontal: 7,
+                vertical: 0
+            }
+        );
+    }
+
+    #[test]
+    fn pitch_is_limited() {
+        let program = parse_program("pitch down 120\nforward 2\naim -200\nforward 1\n").unwrap();
+        let state = program.execute(Submarine3dState::default());
+        assert_eq!(state.pitch, -90);
+        assert_eq!((state.x, state.depth), (0.0, 1.0));
+    }
+}
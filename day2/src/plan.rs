@@ -0,0 +1,302 @@
+//! Working backwards from where the submarine should end up to a shortest list
+//! of commands that takes it there, for producing test missions and checking
+//! which positions can be reached.
+//!
+//! Routes use forward, down and up with positive amounts, as in the puzzle
+//! input, where they can, and back or negative amounts only where they must.
+//! A coordinate too large for one command's amount is covered by several.
+
+use crate::{
+    Command, Direction, Part1SubmarineState, Part2SubmarineState, SubmarinePosition, SubmarineState,
+};
+
+/// An interpretation that routes can be planned for, starting from its default
+/// state.
+pub trait Plan: SubmarineState + Default + Copy {
+    /// A list of commands that ends at `target`, which is a shortest one if
+    /// the coordinates of `target` each fit in a command's amount.
+    fn route(target: SubmarinePosition) -> Vec<Command>;
+}
+
+/// The most commands a planned route is allowed to take.
+pub const MAX_ROUTE_COMMANDS: u64 = 1_000_000;
+
+/// Return a list of commands that takes the submarine from the default state
+/// to `target` under interpretation `S`, a shortest one if both coordinates fit
+/// in a command's amount. The route is checked by replaying it before it is
+/// returned.
+///
+/// Returns `None` if `target` is so far away that its route could take more
+/// than `MAX_ROUTE_COMMANDS` commands.
+pub fn plan<S: Plan>(target: SubmarinePosition) -> Option<Vec<Command>> {
+    // Each axis takes one command for each of the largest amounts it covers,
+    // and a few more to make up the rest.
+    let largest_moves = |coordinate: i64| coordinate.unsigned_abs() / i32::MAX as u64;
+    if largest_moves(target.horizontal) + largest_moves(target.vertical) + 4 > MAX_ROUTE_COMMANDS {
+        return None;
+    }
+    let commands = S::route(target);
+    let reached = commands
+        .iter()
+        .fold(S::default(), |s, c| s.execute(c))
+        .get_position();
+    assert_eq!(reached, target, "planned route {:?} misses", commands);
+    Some(commands)
+}
+
+// A move along the horizontal axis of the given amount.
+fn horizontal(amount: i32) -> Command {
+    match amount.checked_neg() {
+        Some(back) if amount < 0 => Command {
+            direction: Direction::Back,
+            amount: back,
+        },
+        _ => Command {
+            direction: Direction::Forward,
+            amount,
+        },
+    }
+}
+
+// A change in depth, or in aim, of the given amount.
+fn vertical(amount: i32) -> Command {
+    match amount.checked_neg() {
+        Some(up) if amount < 0 => Command {
+            direction: Direction::Up,
+            amount: up,
+        },
+        _ => Command {
+            direction: Direction::Down,
+            amount,
+        },
+    }
+}
+
+// As few moves made with `step` as add up to `distance`, each amount as large
+// as a command allows.
+fn split(distance: i64, step: fn(i32) -> Command) -> Vec<Command> {
+    let mut commands = Vec::new();
+    let mut remaining = distance;
+    while remaining != 0 {
+        let amount = remaining.clamp(i32::MIN.into(), i32::MAX.into()) as i32;
+        commands.push(step(amount));
+        remaining -= i64::from(amount);
+    }
+    commands
+}
+
+// The largest divisor of `n` that is less than `limit`, given that 1 is.
+fn largest_divisor_below(n: u32, limit: u32) -> u32 {
+    let mut largest = 1;
+    let mut i = 1u32;
+    while (i as u64) * (i as u64) <= n as u64 {
+        if n.is_multiple_of(i) {
+            for divisor in [i, n / i] {
+                if divisor < limit {
+                    largest = largest.max(divisor);
+                }
+            }
+        }
+        i += 1;
+    }
+    largest
+}
+
+// Each axis needs a command of its own unless it is already at its target.
+impl Plan for Part1SubmarineState {
+    fn route(target: SubmarinePosition) -> Vec<Command> {
+        let mut commands = split(target.horizontal, horizontal);
+        commands.extend(split(target.vertical, vertical));
+        commands
+    }
+}
+
+// Depth only changes by moving with a non-zero aim, and the aim starts at
+// zero. So reaching a depth takes a change of aim and a move, and a second
+// move, with a different aim, is needed if the horizontal distance isn't a
+// multiple of the depth or if the submarine has to come back to where it
+// started.
+impl Plan for Part2SubmarineState {
+    fn route(target: SubmarinePosition) -> Vec<Command> {
+        let (Ok(h), Ok(d)) = (
+            i32::try_from(target.horizontal),
+            i32::try_from(target.vertical),
+        ) else {
+            return far_route(target);
+        };
+        if d == 0 {
+            return if h == 0 { vec![] } else { vec![horizontal(h)] };
+        }
+        if h == 0 {
+            return vec![horizontal(-1), vertical(d), horizontal(1)];
+        }
+        if (h, d) == (-1, i32::MIN) {
+            // The aim for a single move would be too large, so overshoot and
+            // come back with half of it.
+            return vec![horizontal(-3), vertical(d / 2), horizontal(2)];
+        }
+        if d % h == 0 {
+            return vec![vertical(d / h), horizontal(h)];
+        }
+        // Move most of the way with no aim, then the rest of the way with the
+        // aim that reaches the depth. The last move is the largest divisor of
+        // the depth that leaves some of the way for the first.
+        let last = largest_divisor_below(d.unsigned_abs(), h.unsigned_abs()) as i32 * h.signum();
+        vec![horizontal(h - last), vertical(d / last), horizontal(last)]
+    }
+}
+
+// A route to a target beyond the reach of single commands: move with no aim to
+// within one or two of the target, take on the aim that reaches the depth in
+// the last move, then make it.
+fn far_route(target: SubmarinePosition) -> Vec<Command> {
+    let (h, d) = (target.horizontal, target.vertical);
+    if d == 0 {
+        return split(h, horizontal);
+    }
+    // A last move of 1 fails only for the leftmost target and one of -1 only
+    // for the shallowest, so one of these always works.
+    let last = [1, -1, -2]
+        .into_iter()
+        .find(|&last| h.checked_sub(last).is_some() && d.checked_rem(last) == Some(0))
+        .expect("a last move of 1, -1 or -2 should fit");
+    let mut commands = split(h - last, horizontal);
+    commands.extend(split(d / last, vertical));
+    commands.push(horizontal(last as i32));
+    commands
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn position(horizontal: i32, vertical: i32) -> SubmarinePosition {
+        SubmarinePosition {
+            horizontal: horizontal.into(),
+            vertical: vertical.into(),
+        }
+    }
+
+    fn written(commands: &[Command]) -> Vec<String> {
+        commands.iter().map(Command::to_string).collect()
+    }
+
+    #[test]
+    fn example_target() {
+        let part1 = plan::<Part1SubmarineState>(position(15, 10)).unwrap();
+        assert_eq!(written(&part1), ["forward 15", "down 10"]);
+        let part2 = plan::<Part2SubmarineState>(position(15, 60)).unwrap();
+        assert_eq!(written(&part2), ["down 4", "forward 15"]);
+        let part2 = plan::<Part2SubmarineState>(position(15, 61)).unwrap();
+        assert_eq!(written(&part2), ["forward 14", "down 61", "forward 1"]);
+        let part2 = plan::<Part2SubmarineState>(position(15, 65)).unwrap();
+        assert_eq!(written(&part2), ["forward 2", "down 5", "forward 13"]);
+    }
+
+    #[test]
+    fn no_shorter_route() {
+        // Every command that could be part of a shorter route to a target this
+        // close to the start.
+        let commands: Vec<Command> = [
+            Direction::Forward,
+            Direction::Back,
+            Direction::Down,
+            Direction::Up,
+            Direction::Aim,
+        ]
+        .into_iter()
+        .flat_map(|direction| (-4..=4).map(move |amount| Command { direction, amount }))
+        .collect();
+        fn reachable<S: Plan>(commands: &[Command], length: usize) -> Vec<SubmarinePosition> {
+            let mut states = vec![S::default()];
+            let mut reached = vec![S::default().get_position()];
+            for _ in 0..length {
+                states = states
+                    .iter()
+                    .flat_map(|&s| commands.iter().map(move |c| s.execute(c)))
+                    .collect();
+                reached.extend(states.iter().map(|&s| s.get_position()));
+            }
+            reached
+        }
+        for length in 0..3 {
+            let part1 = reachable::<Part1SubmarineState>(&commands, length);
+            let part2 = reachable::<Part2SubmarineState>(&commands, length);
+            for h in -4..=4 {
+                for d in -4..=4 {
+                    let target = position(h, d);
+                    if plan::<Part1SubmarineState>(target).unwrap().len() > length {
+                        assert!(!part1.contains(&target), "{:?}", target);
+                    }
+                    if plan::<Part2SubmarineState>(target).unwrap().len() > length {
+                        assert!(!part2.contains(&target), "{:?}", target);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn extreme_targets() {
+        for h in [-3, -2, -1, 1, 2, 3, i32::MIN, i32::MAX] {
+            for d in [i32::MIN, i32::MIN + 1, i32::MAX] {
+                assert!(plan::<Part2SubmarineState>(position(h, d)).unwrap().len() <= 3);
+            }
+        }
+    }
+
+    #[test]
+    fn far_targets() {
+        let far = |horizontal, vertical| SubmarinePosition {
+            horizontal,
+            vertical,
+        };
+        let part1 = plan::<Part1SubmarineState>(far(3_000_000_000, -5)).unwrap();
+        assert_eq!(
+            written(&part1),
+            ["forward 2147483647", "forward 852516353", "up 5"]
+        );
+        let part2 = plan::<Part2SubmarineState>(far(3_000_000_000, 0)).unwrap();
+        assert_eq!(written(&part2), ["forward 2147483647", "forward 852516353"]);
+        let part2 = plan::<Part2SubmarineState>(far(0, 3_000_000_000)).unwrap();
+        assert_eq!(
+            written(&part2),
+            ["back 1", "down 2147483647", "down 852516353", "forward 1"]
+        );
+        // As far as a route can go on both axes at once.
+        let reach = (MAX_ROUTE_COMMANDS as i64 / 2 - 2) * i32::MAX as i64;
+        for target in [
+            far(reach, -reach),
+            far(-reach, reach),
+            far(-1, -reach),
+            far(reach, 0),
+        ] {
+            let part1 = plan::<Part1SubmarineState>(target).unwrap();
+            let part2 = plan::<Part2SubmarineState>(target).unwrap();
+            assert!(part1.len().max(part2.len()) as u64 <= MAX_ROUTE_COMMANDS);
+        }
+        assert_eq!(plan::<Part1SubmarineState>(far(i64::MAX, 0)), None);
+        assert_eq!(plan::<Part2SubmarineState>(far(1, i64::MIN)), None);
+    }
+
+    proptest! {
+        #[test]
+        fn every_far_target_is_reachable(
+            h in -(1i64 << 40)..(1 << 40),
+            d in -(1i64 << 40)..(1 << 40),
+        ) {
+            let target = SubmarinePosition { horizontal: h, vertical: d };
+            prop_assert!(plan::<Part1SubmarineState>(target).is_some());
+            prop_assert!(plan::<Part2SubmarineState>(target).is_some());
+        }
+
+        #[test]
+        fn every_target_is_reachable(h in any::<i32>(), d in any::<i32>()) {
+            let part1 = plan::<Part1SubmarineState>(position(h, d)).unwrap();
+            prop_assert!(part1.len() <= 2);
+            let part2 = plan::<Part2SubmarineState>(position(h, d)).unwrap();
+            prop_assert!(part2.len() <= 3);
+        }
+    }
+}
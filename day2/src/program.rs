@@ -0,0 +1,242 @@
+//! A small language for scripting longer missions than a flat list of
+//! commands. Each line holds a command, the start of a block such as
+//! `repeat 3 {`, or the `}` that closes it. Anything after `#` is a comment,
+//! and blank lines are ignored.
+
+use crate::{parse_command, Command, SubmarineState};
+use common::{Line, ParseError};
+use std::convert::Infallible;
+
+/// A step of a mission, along with the line it came from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Statement {
+    Command {
+        command: Command,
+        line: usize,
+    },
+    Repeat {
+        count: u32,
+        body: Vec<Statement>,
+        line: usize,
+    },
+}
+
+impl Statement {
+    /// The line the statement starts on.
+    pub fn line(&self) -> usize {
+        match self {
+            Statement::Command { line, .. } | Statement::Repeat { line, .. } => *line,
+        }
+    }
+
+    /// Number of commands the statement carries out, with repeat blocks
+    /// expanded, or `u64::MAX` if there are more.
+    pub fn expanded_len(&self) -> u64 {
+        match self {
+            Statement::Command { .. } => 1,
+            Statement::Repeat { count, body, .. } => body
+                .iter()
+                .fold(0u64, |len, s| len.saturating_add(s.expanded_len()))
+                .saturating_mul(*count as u64),
+        }
+    }
+}
+
+/// A parsed mission.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Program {
+    pub statements: Vec<Statement>,
+}
+
+impl Program {
+    /// Call `f` with each command that running the program would carry out,
+    /// in order, along with the line the command is on. Stops at the first
+    /// error.
+    pub fn try_for_each<E>(
+        &self,
+        mut f: impl FnMut(&Command, usize) -> Result<(), E>,
+    ) -> Result<(), E> {
+        fn visit<E>(
+            statements: &[Statement],
+            f: &mut impl FnMut(&Command, usize) -> Result<(), E>,
+        ) -> Result<(), E> {
+            for statement in statements {
+                match statement {
+                    Statement::Command { command, line } => f(command, *line)?,
+                    Statement::Repeat { count, body, .. } => {
+                        for _ in 0..*count {
+                            visit(body, f)?;
+                        }
+                    }
+                }
+            }
+            Ok(())
+        }
+        visit(&self.statements, &mut f)
+    }
+
+    /// Call `f` with each command that running the program would carry out,
+    /// in order, along with the line the command is on.
+    pub fn for_each(&self, mut f: impl FnMut(&Command, usize)) {
+        let _ = self.try_for_each(|command, line| -> Result<(), Infallible> {
+            f(command, line);
+            Ok(())
+        });
+    }
+
+    /// Run the program from `state` and return the final state.
+    pub fn execute<S: SubmarineState>(&self, state: S) -> S {
+        fn run<S: SubmarineState>(statements: &[Statement], state: S) -> S {
+            statements
+                .iter()
+                .fold(state, |state, statement| match statement {
+                    Statement::Command { command, .. } => state.execute(command),
+                    Statement::Repeat { count, body, .. } => {
+                        (0..*count).fold(state, |state, _| run(body, state))
+                    }
+                })
+        }
+        run(&self.statements, state)
+    }
+}
+
+// A block that has been opened but not yet closed.
+struct Block {
+    count: u32,
+    line: usize,
+    body: Vec<Statement>,
+}
+
+/// Parse a program, such as the puzzle input or a longer mission script.
+pub fn parse_program(input: &str) -> Result<Program, ParseError> {
+    let mut statements = Vec::new();
+    let mut blocks: Vec<Block> = Vec::new();
+    for line in common::lines(input) {
+        let text = line.text.split('#').next().unwrap().trim();
+        let statement = if text.is_empty() {
+            continue;
+        } else if text == "}" {
+            let block = blocks
+                .pop()
+                .ok_or_else(|| line.error(text, "a command or a repeat block"))?;
+            Statement::Repeat {
+                count: block.count,
+                body: block.body,
+                line: block.line,
+            }
+        } else if text.split_whitespace().next() == Some("repeat") {
+            blocks.push(parse_repeat(&line, &text["repeat".len()..])?);
+            continue;
+        } else {
+            Statement::Command {
+                command: parse_command(&line, text)?,
+                line: line.number,
+            }
+        };
+        match blocks.last_mut() {
+            Some(block) => block.body.push(statement),
+            None => statements.push(statement),
+        }
+    }
+    match blocks.last() {
+        Some(block) => {
+            let expected = format!("'}}' to close the repeat on line {}", block.line);
+            Err(common::end_of_input(input, &expected))
+        }
+        None => Ok(Program { statements }),
+    }
+}
+
+// Parse the rest of a line that starts with `repeat`, which should be a count
+// followed by `{`.
+fn parse_repeat(line: &Line, rest: &str) -> Result<Block, ParseError> {
+    let mut fields = rest.split_whitespace();
+    let count = fields.next().unwrap_or(line.end());
+    let count = line.parse::<u32>(count, "a repeat count")?;
+    match fields.next() {
+        Some("{") => {}
+        Some(field) => return Err(line.error(field, "'{'")),
+        None => return Err(line.error(line.end(), "'{'")),
+    }
+    if let Some(field) = fields.next() {
+        return Err(line.error(field, "the end of the line"));
+    }
+    Ok(Block {
+        count,
+        line: line.number,
+        body: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Direction, Part1SubmarineState, Part2SubmarineState, SubmarinePosition};
+
+    const MISSION: &str = "\
+# Dive, then sweep back and forth.
+down 10
+repeat 2 {
+    forward 5   # out
+    repeat 3 {
+        down 1
+    }
+    back 2
+}
+
+aim -1
+forward -4
+";
+
+    #[test]
+    fn parses_nested_blocks() {
+        let program = parse_program(MISSION).unwrap();
+        assert_eq!(program.statements.len(), 4);
+        let mut lines = Vec::new();
+        program.for_each(|command, line| lines.push((command.direction, line)));
+        assert_eq!(lines.len(), 1 + 2 * 5 + 2);
+        assert_eq!(lines[0], (Direction::Down, 2));
+        assert_eq!(lines[1], (Direction::Forward, 4));
+        assert_eq!(lines[5], (Direction::Back, 8));
+        assert_eq!(lines[12], (Direction::Forward, 12));
+    }
+
+    #[test]
+    fn executes_each_interpretation() {
+        let program = parse_program(MISSION).unwrap();
+        let part1 = program.execute(Part1SubmarineState::default());
+        assert_eq!(
+            part1.get_position(),
+            SubmarinePosition {
+                horizontal: 2,
+                vertical: 16
+            }
+        );
+        // Aim reaches 13 after the first pass and 16 after the second, then is
+        // set to -1 for the final move backwards.
+        let part2 = program.execute(Part2SubmarineState::default());
+        assert_eq!(
+            part2.get_position(),
+            SubmarinePosition {
+                horizontal: 2,
+                vertical: 10 * 5 - 13 * 2 + 13 * 5 - 16 * 2 + 4
+            }
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let error = parse_program("forward 1\n}\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+        let error = parse_program("repeat 2 {\n  forward 1\n").unwrap_err();
+        assert_eq!(error.line, 3);
+        assert_eq!(error.expected, "'}' to close the repeat on line 1");
+        let error = parse_program("repeat x {\n}\n").unwrap_err();
+        assert_eq!(
+            (error.column, error.expected.as_str()),
+            (8, "a repeat count")
+        );
+        let error = parse_program("  sideways 3\n").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 3));
+    }
+}
@@ -0,0 +1,48 @@
+//! Straightforward implementations of each part, for checking the real
+//! solutions against.
+
+use crate::{Command, Direction};
+
+pub fn part1(commands: &[Command]) -> i128 {
+    let (mut horizontal, mut depth) = (0i64, 0i64);
+    for c in commands {
+        let amount = c.amount as i64;
+        match c.direction {
+            Direction::Forward => horizontal += amount,
+            Direction::Back => horizontal -= amount,
+            Direction::Down => depth += amount,
+            Direction::Up => depth -= amount,
+            Direction::Aim
+            | Direction::TurnLeft
+            | Direction::TurnRight
+            | Direction::PitchUp
+            | Direction::PitchDown => {}
+        }
+    }
+    horizontal as i128 * depth as i128
+}
+
+pub fn part2(commands: &[Command]) -> i128 {
+    let (mut horizontal, mut depth, mut aim) = (0i64, 0i64, 0i64);
+    for c in commands {
+        let amount = c.amount as i64;
+        match c.direction {
+            Direction::Forward => {
+                horizontal += amount;
+                depth += aim * amount;
+            }
+            Direction::Back => {
+                horizontal -= amount;
+                depth -= aim * amount;
+            }
+            Direction::Down => aim += amount,
+            Direction::Up => aim -= amount,
+            Direction::Aim => aim = amount,
+            Direction::TurnLeft
+            | Direction::TurnRight
+            | Direction::PitchUp
+            | Direction::PitchDown => {}
+        }
+    }
+    horizontal as i128 * depth as i128
+}
@@ -0,0 +1,353 @@
+//! An interactive console for driving the submarine by hand under both
+//! interpretations at once, one command at a time.
+
+use crate::program::parse_program;
+use crate::{
+    out_of_range, parse_command, Command, Overflow, Part1SubmarineState, Part2SubmarineState,
+    SubmarineState,
+};
+use common::{Line, ParseError};
+use std::fs;
+use std::io::{self, BufRead, Write};
+
+const HELP: &str = "\
+Type a command such as 'forward 5' to carry it out, or:
+  undo         take back the last command
+  reset        go back to the start
+  save <FILE>  write the commands so far to FILE
+  load <FILE>  start again from the commands in FILE
+  quit         leave";
+
+/// Most commands a loaded script may carry out, since each one is kept along
+/// with the state after it so that it can be undone.
+pub const MAX_LOADED_COMMANDS: u64 = 1_000_000;
+
+/// The commands carried out so far, and the state under each interpretation
+/// after each of them.
+#[derive(Clone, Debug)]
+pub struct Session {
+    commands: Vec<Command>,
+    // The first entry is the starting state, so there is always one more than
+    // there are commands.
+    states: Vec<(Part1SubmarineState, Part2SubmarineState)>,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Session {
+            commands: Vec::new(),
+            states: vec![Default::default()],
+        }
+    }
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn commands(&self) -> &[Command] {
+        &self.commands
+    }
+
+    /// The current state under each interpretation.
+    pub fn state(&self) -> (Part1SubmarineState, Part2SubmarineState) {
+        *self.states.last().unwrap()
+    }
+
+    /// Carry out a command. Returns false, leaving the session unchanged, if it
+    /// would take the submarine out of range under either interpretation.
+    pub fn execute(&mut self, command: Command) -> bool {
+        let (part1, part2) = self.state();
+        match (part1.try_execute(&command), part2.try_execute(&command)) {
+            (Some(part1), Some(part2)) => {
+                self.states.push((part1, part2));
+                self.commands.push(command);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Take back the last command, returning it, or `None` if there are none.
+    pub fn undo(&mut self) -> Option<Command> {
+        let command = self.commands.pop()?;
+        self.states.pop();
+        Some(command)
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Write the commands so far as a script, one per line.
+    pub fn save(&self, out: &mut dyn Write) -> io::Result<()> {
+        for command in &self.commands {
+            writeln!(out, "{}", command)?;
+        }
+        Ok(())
+    }
+
+    /// Start again from the commands a script carries out, with repeat blocks
+    /// expanded so that each can be undone on its own. The session is left
+    /// unchanged if the script doesn't parse, carries out more than
+    /// `MAX_LOADED_COMMANDS` commands, or takes the submarine out of range.
+    pub fn load(&mut self, script: &str) -> Result<(), ParseError> {
+        let program = parse_program(script)?;
+        let mut len = 0u64;
+        for statement in &program.statements {
+            len = len.saturating_add(statement.expanded_len());
+            if len > MAX_LOADED_COMMANDS {
+                let line = common::lines(script).nth(statement.line() - 1).unwrap();
+                let text = line.text.split('#').next().unwrap().trim();
+                let expected = format!(
+                    "a script of at most {} commands with repeats expanded",
+                    MAX_LOADED_COMMANDS
+                );
+                return Err(line.error(text, &expected));
+            }
+        }
+        let mut session = Session::new();
+        program
+            .try_for_each(|command, line| match session.execute(*command) {
+                true => Ok(()),
+                false => Err(Overflow { line }),
+            })
+            .map_err(|overflow| overflow.parse_error(script))?;
+        *self = session;
+        Ok(())
+    }
+}
+
+fn describe(state: impl SubmarineState + Copy) -> String {
+    let aim = state.get_aim();
+    let position = state.get_position();
+    let mut text = format!(
+        "horizontal {}, depth {}",
+        position.horizontal, position.vertical
+    );
+    if let Some(aim) = aim {
+        text += &format!(", aim {}", aim);
+    }
+    text
+}
+
+fn write_state(session: &Session, out: &mut dyn Write) -> io::Result<()> {
+    let (part1, part2) = session.state();
+    writeln!(out, "part 1: {}", describe(part1))?;
+    writeln!(out, "part 2: {}", describe(part2))
+}
+
+// Carry out one line typed at the prompt. Returns false when the session should
+// end.
+fn respond(session: &mut Session, line: &Line, out: &mut dyn Write) -> io::Result<bool> {
+    let text = line.text.trim();
+    let (word, argument) = match text.split_once(char::is_whitespace) {
+        Some((word, argument)) => (word, argument.trim()),
+        None => (text, ""),
+    };
+    match (word, argument) {
+        ("", _) => return Ok(true),
+        ("quit" | "exit", "") => return Ok(false),
+        ("help", "") => writeln!(out, "{}", HELP)?,
+        ("undo", "") => match session.undo() {
+            Some(command) => {
+                writeln!(out, "undid '{}'", command)?;
+                write_state(session, out)?;
+            }
+            None => writeln!(out, "error: nothing to undo")?,
+        },
+        ("reset", "") => {
+            session.reset();
+            write_state(session, out)?;
+        }
+        ("save", path) if !path.is_empty() => {
+            let result = fs::File::create(path).and_then(|mut file| session.save(&mut file));
+            match result {
+                Ok(()) => writeln!(out, "saved {} commands to {}", session.commands.len(), path)?,
+                Err(e) => writeln!(out, "error: failed to write {}: {}", path, e)?,
+            }
+        }
+        ("load", path) if !path.is_empty() => {
+            let result = fs::read_to_string(path)
+                .map_err(|e| format!("failed to read {}: {}", path, e))
+                .and_then(|script| {
+                    session
+                        .load(&script)
+                        .map_err(|e| e.with_file(path).to_string())
+                });
+            match result {
+                Ok(()) => {
+                    writeln!(
+                        out,
+                        "loaded {} commands from {}",
+                        session.commands.len(),
+                        path
+                    )?;
+                    write_state(session, out)?;
+                }
+                Err(message) => writeln!(out, "error: {}", message)?,
+            }
+        }
+        _ => match parse_command(line, text) {
+            Ok(command) if session.execute(command) => write_state(session, out)?,
+            Ok(_) => writeln!(out, "error: {}", out_of_range(line))?,
+            Err(e) => writeln!(out, "error: {}", e)?,
+        },
+    }
+    Ok(true)
+}
+
+/// Read commands from `input` until it ends or the user quits, printing the
+/// state under both interpretations after each one.
+pub fn run(input: impl BufRead, out: &mut dyn Write) -> io::Result<()> {
+    let mut session = Session::new();
+    writeln!(out, "Type 'help' for the list of commands.")?;
+    let mut input = input.lines();
+    for number in 1.. {
+        write!(out, "> ")?;
+        out.flush()?;
+        let Some(text) = input.next().transpose()? else {
+            writeln!(out)?;
+            break;
+        };
+        let line = Line {
+            number,
+            text: &text,
+        };
+        if !respond(&mut session, &line, out)? {
+            break;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Direction, SubmarinePosition};
+
+    const EXAMPLE: &str = include_str!("example.txt");
+
+    fn transcript(input: &str) -> String {
+        let mut out = Vec::new();
+        run(input.as_bytes(), &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn session_matches_program() {
+        let mut session = Session::new();
+        session.load(EXAMPLE).unwrap();
+        let (part1, part2) = session.state();
+        assert_eq!(
+            (
+                part1.get_position().product(),
+                part2.get_position().product()
+            ),
+            (150, 900)
+        );
+        assert_eq!(session.undo().unwrap().to_string(), "forward 2");
+        assert_eq!(
+            session.state().0.get_position(),
+            SubmarinePosition {
+                horizontal: 13,
+                vertical: 10
+            }
+        );
+        let mut saved = Vec::new();
+        session.save(&mut saved).unwrap();
+        let mut reloaded = Session::new();
+        reloaded.load(std::str::from_utf8(&saved).unwrap()).unwrap();
+        assert_eq!(reloaded.commands(), session.commands());
+        session.reset();
+        assert!(session.commands().is_empty());
+        assert!(session.undo().is_none());
+    }
+
+    #[test]
+    fn prints_state_after_each_command() {
+        let output = transcript("forward 5\ndown 5\nforward 8\nundo\nsideways 1\nquit\nup 1\n");
+        let expected = "\
+Type 'help' for the list of commands.
+> part 1: horizontal 5, depth 0
+part 2: horizontal 5, depth 0, aim 0
+> part 1: horizontal 5, depth 5
+part 2: horizontal 5, depth 0, aim 5
+> part 1: horizontal 13, depth 5
+part 2: horizontal 13, depth 40, aim 5
+> undid 'forward 8'
+part 1: horizontal 5, depth 5
+part 2: horizontal 5, depth 0, aim 5
+> error: 5:1: expected forward, back, down, up, aim, turn or pitch, found 'sideways'
+> ";
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn rejects_commands_out_of_range() {
+        let output = transcript(
+            "aim 2147483647\nforward 2147483647\nforward 2147483647\nforward 2147483647\nundo\n",
+        );
+        let expected = "\
+Type 'help' for the list of commands.
+> part 1: horizontal 0, depth 0
+part 2: horizontal 0, depth 0, aim 2147483647
+> part 1: horizontal 2147483647, depth 0
+part 2: horizontal 2147483647, depth 4611686014132420609, aim 2147483647
+> part 1: horizontal 4294967294, depth 0
+part 2: horizontal 4294967294, depth 9223372028264841218, aim 2147483647
+> error: 4:1: expected a command that keeps the submarine within range, found 'forward 2147483647'
+> undid 'forward 2147483647'
+part 1: horizontal 2147483647, depth 0
+part 2: horizontal 2147483647, depth 4611686014132420609, aim 2147483647
+> 
+";
+        assert_eq!(output, expected);
+        let mut session = Session::new();
+        session.execute(Command {
+            direction: Direction::Down,
+            amount: 1,
+        });
+        let error = session
+            .load("aim 2147483647\nrepeat 3 {\n  forward 2147483647\n}\n")
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "3:3: expected a command that keeps the submarine within range, found 'forward 2147483647'"
+        );
+        assert_eq!(session.commands().len(), 1);
+        let error = session
+            .load("down 1\nrepeat 4294967295 {\n  forward 0\n}\n")
+            .unwrap_err();
+        assert_eq!(
+            (error.line, error.expected.as_str()),
+            (
+                2,
+                "a script of at most 1000000 commands with repeats expanded"
+            )
+        );
+        assert_eq!(session.commands().len(), 1);
+        let script = format!("repeat {} {{\n  up 0\n}}\n", MAX_LOADED_COMMANDS);
+        session.load(&script).unwrap();
+        assert_eq!(session.commands().len() as u64, MAX_LOADED_COMMANDS);
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path = std::env::temp_dir().join(format!("day2-repl-{}.txt", std::process::id()));
+        let path = path.to_str().unwrap();
+        let output = transcript(&format!(
+            "forward 2\naim 3\nforward 1\nsave {0}\nreset\nload {0}\nload {0}.missing\n",
+            path
+        ));
+        fs::remove_file(path).unwrap();
+        assert!(output.contains(&format!("saved 3 commands to {}\n", path)));
+        assert!(output.contains(&format!(
+            "loaded 3 commands from {}\npart 1: horizontal 3, depth 0\npart 2: horizontal 3, depth 3, aim 3\n",
+            path
+        )));
+        assert!(output.contains(&format!("error: failed to read {}.missing: ", path)));
+        assert!(output.ends_with("> \n"));
+    }
+}
@@ -0,0 +1,130 @@
+//! Reading commands straight from a reader, for inputs too large to hold as
+//! text, without allocating for each line.
+
+use crate::{
+    parse_input_line, Command, Part1SubmarineState, Part2SubmarineState, SubmarineState, IN_RANGE,
+};
+use common::{Line, ParseError, ReadError};
+use std::io::{self, BufRead};
+
+/// Iterates over the commands read from `reader`, one per line, reusing a
+/// single line buffer.
+pub struct Commands<R> {
+    reader: R,
+    buffer: Vec<u8>,
+    line_number: usize,
+}
+
+impl<R: BufRead> Commands<R> {
+    pub fn new(reader: R) -> Self {
+        Commands {
+            reader,
+            buffer: Vec::new(),
+            line_number: 0,
+        }
+    }
+
+    /// Number of lines read so far, which is the line of the last command.
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+}
+
+impl<R: BufRead> Iterator for Commands<R> {
+    type Item = Result<Command, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.buffer.clear();
+        match self.reader.read_until(b'\n', &mut self.buffer) {
+            Ok(0) => None,
+            Ok(_) => {
+                self.line_number += 1;
+                let bytes = self.buffer.strip_suffix(b"\n").unwrap_or(&self.buffer);
+                let bytes = bytes.strip_suffix(b"\r").unwrap_or(bytes);
+                let text = match std::str::from_utf8(bytes) {
+                    Ok(text) => text,
+                    Err(_) => {
+                        let message = format!("line {} is not valid UTF-8", self.line_number);
+                        return Some(Err(
+                            io::Error::new(io::ErrorKind::InvalidData, message).into()
+                        ));
+                    }
+                };
+                let line = Line {
+                    number: self.line_number,
+                    text,
+                };
+                Some(parse_input_line(&line).map_err(ReadError::from))
+            }
+            Err(e) => Some(Err(e.into())),
+        }
+    }
+}
+
+/// Answer both parts in a single pass over the commands read from `reader`,
+/// without holding them in memory.
+pub fn solve_stream(reader: impl BufRead) -> Result<[i128; 2], ReadError> {
+    let mut states = (
+        Part1SubmarineState::default(),
+        Part2SubmarineState::default(),
+    );
+    let mut commands = Commands::new(reader);
+    while let Some(command) = commands.next() {
+        let command = command?;
+        let (part1, part2) = states;
+        states = part1
+            .try_execute(&command)
+            .zip(part2.try_execute(&command))
+            .ok_or_else(|| {
+                let found = command.to_string();
+                ParseError::new(commands.line_number(), 1, IN_RANGE, &found)
+            })?;
+    }
+    let (part1, part2) = states;
+    Ok([
+        part1.get_position().product(),
+        part2.get_position().product(),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("example.txt");
+
+    #[test]
+    fn matches_parse_input() {
+        let commands: Vec<Command> = Commands::new(EXAMPLE.as_bytes())
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(commands, crate::parse_input(EXAMPLE).unwrap());
+        let crlf = EXAMPLE.replace('\n', "\r\n");
+        let commands: Vec<Command> = Commands::new(crlf.as_bytes())
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(commands, crate::parse_input(EXAMPLE).unwrap());
+    }
+
+    #[test]
+    fn solves_stream() {
+        assert_eq!(solve_stream(EXAMPLE.as_bytes()).unwrap(), [150, 900]);
+        let overflow =
+            "aim 2147483647\nforward 2147483647\nforward 2147483647\nforward 2147483647\n";
+        match solve_stream(overflow.as_bytes()) {
+            Err(ReadError::Parse(e)) => assert_eq!((e.line, e.expected.as_str()), (4, IN_RANGE)),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let mut commands = Commands::new(&b"forward 1\ndown x\n\xff\n"[..]);
+        assert!(commands.next().unwrap().is_ok());
+        match commands.next().unwrap() {
+            Err(ReadError::Parse(e)) => assert_eq!((e.line, e.column), (2, 6)),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(matches!(commands.next(), Some(Err(ReadError::Io(_)))));
+    }
+}
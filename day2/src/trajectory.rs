@@ -0,0 +1,177 @@
+//! Every position the submarine passes through on the way to its final one,
+//! for inspecting and plotting how an interpretation moves it.
+
+use crate::program::Program;
+use crate::{Overflow, SubmarinePosition, SubmarineState};
+use std::io::{self, Write};
+
+/// The state of the submarine after a command.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Waypoint {
+    /// Line of the command that led here, or `None` for the starting point.
+    pub line: Option<usize>,
+    pub position: SubmarinePosition,
+    pub aim: Option<i64>,
+}
+
+/// The waypoints visited by running a program, starting with the initial
+/// state.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Trajectory {
+    pub waypoints: Vec<Waypoint>,
+}
+
+impl Trajectory {
+    /// Run `program` from `state`, recording the state after every command, or
+    /// return the first command that takes the submarine out of range.
+    pub fn record<S: SubmarineState + Copy>(state: S, program: &Program) -> Result<Self, Overflow> {
+        let waypoint = |state: S, line| Waypoint {
+            line,
+            position: state.get_position(),
+            aim: state.get_aim(),
+        };
+        let mut waypoints = vec![waypoint(state, None)];
+        let mut state = state;
+        program.try_for_each(|command, line| {
+            state = state.try_execute(command).ok_or(Overflow { line })?;
+            waypoints.push(waypoint(state, Some(line)));
+            Ok(())
+        })?;
+        Ok(Trajectory { waypoints })
+    }
+}
+
+fn optional(value: Option<impl ToString>) -> String {
+    value.map_or(String::new(), |v| v.to_string())
+}
+
+/// Write named trajectories as CSV, one row per waypoint. The aim is left empty
+/// for interpretations without one.
+pub fn write_csv(trajectories: &[(&str, &Trajectory)], out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "interpretation,step,line,horizontal,depth,aim")?;
+    for (name, trajectory) in trajectories {
+        for (step, waypoint) in trajectory.waypoints.iter().enumerate() {
+            writeln!(
+                out,
+                "{},{},{},{},{},{}",
+                name,
+                step,
+                optional(waypoint.line),
+                waypoint.position.horizontal,
+                waypoint.position.vertical,
+                optional(waypoint.aim)
+            )?;
+        }
+    }
+    Ok(())
+}
+
+const COLOURS: [&str; 4] = ["#1f77b4", "#d62728", "#2ca02c", "#9467bd"];
+
+/// Write named trajectories as an SVG plot of depth against horizontal
+/// position, with depth increasing downwards, as it does under the sea.
+pub fn write_svg(trajectories: &[(&str, &Trajectory)], out: &mut dyn Write) -> io::Result<()> {
+    let positions = || {
+        trajectories
+            .iter()
+            .flat_map(|(_, t)| t.waypoints.iter().map(|w| w.position))
+    };
+    let min_x = positions().map(|p| p.horizontal).min().unwrap_or(0);
+    let max_x = positions().map(|p| p.horizontal).max().unwrap_or(0);
+    let min_y = positions().map(|p| p.vertical).min().unwrap_or(0);
+    let max_y = positions().map(|p| p.vertical).max().unwrap_or(0);
+    // Pad the plot so the lines don't touch the edges, and keep it from
+    // collapsing when the submarine only moves along one axis.
+    let width = (max_x as i128 - min_x as i128).max(1);
+    let height = (max_y as i128 - min_y as i128).max(1);
+    let padding = width.max(height) / 20 + 1;
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}" width="800" height="600" preserveAspectRatio="none">"#,
+        min_x as i128 - padding,
+        min_y as i128 - padding,
+        width + 2 * padding,
+        height + 2 * padding
+    )?;
+    for (i, (name, trajectory)) in trajectories.iter().enumerate() {
+        let colour = COLOURS[i % COLOURS.len()];
+        writeln!(out, "  <g>")?;
+        writeln!(out, "    <title>{}</title>", name)?;
+        write!(
+            out,
+            r#"    <polyline fill="none" stroke="{}" stroke-width="2" vector-effect="non-scaling-stroke" points=""#,
+            colour
+        )?;
+        for (j, waypoint) in trajectory.waypoints.iter().enumerate() {
+            let separator = if j == 0 { "" } else { " " };
+            write!(
+                out,
+                "{}{},{}",
+                separator, waypoint.position.horizontal, waypoint.position.vertical
+            )?;
+        }
+        writeln!(out, r#""/>"#)?;
+        writeln!(out, "  </g>")?;
+    }
+    writeln!(out, "</svg>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::program::parse_program;
+    use crate::{Part1SubmarineState, Part2SubmarineState};
+
+    const EXAMPLE: &str = include_str!("example.txt");
+
+    #[test]
+    fn example_trajectories() {
+        let program = parse_program(EXAMPLE).unwrap();
+        let part1 = Trajectory::record(Part1SubmarineState::default(), &program).unwrap();
+        let part2 = Trajectory::record(Part2SubmarineState::default(), &program).unwrap();
+        assert_eq!(part1.waypoints.len(), 7);
+        assert_eq!(
+            part1.waypoints[3],
+            Waypoint {
+                line: Some(3),
+                position: SubmarinePosition {
+                    horizontal: 13,
+                    vertical: 5
+                },
+                aim: None
+            }
+        );
+        let last = part2.waypoints.last().unwrap();
+        assert_eq!(last.position.product(), 900);
+        assert_eq!(last.aim, Some(10));
+    }
+
+    #[test]
+    fn csv() {
+        let program = parse_program("down 2\nforward 3\n").unwrap();
+        let part2 = Trajectory::record(Part2SubmarineState::default(), &program).unwrap();
+        let mut out = Vec::new();
+        write_csv(&[("part2", &part2)], &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "interpretation,step,line,horizontal,depth,aim\n\
+             part2,0,,0,0,0\n\
+             part2,1,1,0,0,2\n\
+             part2,2,2,3,6,2\n"
+        );
+    }
+
+    #[test]
+    fn svg() {
+        let program = parse_program(EXAMPLE).unwrap();
+        let part1 = Trajectory::record(Part1SubmarineState::default(), &program).unwrap();
+        let part2 = Trajectory::record(Part2SubmarineState::default(), &program).unwrap();
+        let mut out = Vec::new();
+        write_svg(&[("part1", &part1), ("part2", &part2)], &mut out).unwrap();
+        let svg = String::from_utf8(out).unwrap();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert_eq!(svg.matches("<polyline").count(), 2);
+        assert!(svg.contains(r#"points="0,0 5,0 5,5 13,5 13,2 13,10 15,10""#));
+    }
+}
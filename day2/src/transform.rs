@@ -0,0 +1,477 @@
+//! Commands as transforms of the submarine's state that can be composed in any
+//! grouping, so a long list of commands can be split into chunks, each chunk
+//! reduced to a single transform on its own thread, and the results merged.
+//!
+//! Arithmetic wraps, so that composing in a different grouping can't overflow
+//! where running the commands in order wouldn't. Results match the sequential
+//! fold whenever that doesn't overflow. `checked_execute_program` instead finds
+//! where a program goes out of range, without expanding its repeat blocks.
+
+use crate::program::{Program, Statement};
+use crate::{
+    Command, Direction, Overflow, Part1SubmarineState, Part2SubmarineState, SubmarinePosition,
+    SubmarineState,
+};
+use std::thread;
+
+/// A change to the submarine's state. Transforms form a monoid under `then`.
+pub trait Transform: Copy + Send + Sync {
+    /// The transform that changes nothing.
+    const IDENTITY: Self;
+
+    /// The transform that applies `self` and then `next`.
+    fn then(self, next: Self) -> Self;
+
+    /// The transform that applies `self` `count` times.
+    fn repeat(self, mut count: u32) -> Self {
+        let (mut result, mut power) = (Self::IDENTITY, self);
+        while count > 0 {
+            if count & 1 == 1 {
+                result = result.then(power);
+            }
+            power = power.then(power);
+            count >>= 1;
+        }
+        result
+    }
+}
+
+/// A transform that can also be composed without wrapping.
+pub trait CheckedTransform: Transform {
+    /// The transform that applies `self` and then `next`, or `None` if it is
+    /// beyond the range of the transform.
+    fn checked_then(self, next: Self) -> Option<Self>;
+
+    /// The transform that applies `self` `count` times, or `None` if it, or
+    /// any power of `self` up to it, is beyond the range of the transform.
+    fn checked_repeat(self, mut count: u32) -> Option<Self> {
+        let (mut result, mut power) = (Self::IDENTITY, self);
+        loop {
+            if count & 1 == 1 {
+                result = result.checked_then(power)?;
+            }
+            count >>= 1;
+            if count == 0 {
+                return Some(result);
+            }
+            // Only square while a higher power is still needed, so as not to
+            // overflow on a power that the count doesn't reach.
+            power = power.checked_then(power)?;
+        }
+    }
+}
+
+/// An interpretation whose commands can be represented as transforms.
+pub trait ComposableState: SubmarineState + Copy + Send {
+    type Transform: Transform;
+
+    fn transform(c: &Command) -> Self::Transform;
+
+    fn apply(self, t: &Self::Transform) -> Self;
+}
+
+/// Part 1 commands move the submarine by a fixed amount.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Translation {
+    pub horizontal: i64,
+    pub vertical: i64,
+}
+
+impl Transform for Translation {
+    const IDENTITY: Self = Translation {
+        horizontal: 0,
+        vertical: 0,
+    };
+
+    fn then(self, next: Self) -> Self {
+        Translation {
+            horizontal: self.horizontal.wrapping_add(next.horizontal),
+            vertical: self.vertical.wrapping_add(next.vertical),
+        }
+    }
+}
+
+impl ComposableState for Part1SubmarineState {
+    type Transform = Translation;
+
+    fn transform(c: &Command) -> Translation {
+        let amount = c.amount as i64;
+        let (horizontal, vertical) = match c.direction {
+            Direction::Forward => (amount, 0),
+            Direction::Back => (-amount, 0),
+            Direction::Down => (0, amount),
+            Direction::Up => (0, -amount),
+            Direction::Aim
+            | Direction::TurnLeft
+            | Direction::TurnRight
+            | Direction::PitchUp
+            | Direction::PitchDown => (0, 0),
+        };
+        Translation {
+            horizontal,
+            vertical,
+        }
+    }
+
+    fn apply(self, t: &Translation) -> Self {
+        Part1SubmarineState(SubmarinePosition {
+            horizontal: self.0.horizontal.wrapping_add(t.horizontal),
+            vertical: self.0.vertical.wrapping_add(t.vertical),
+        })
+    }
+}
+
+impl CheckedTransform for Translation {
+    fn checked_then(self, next: Self) -> Option<Self> {
+        Some(Translation {
+            horizontal: self.horizontal.checked_add(next.horizontal)?,
+            vertical: self.vertical.checked_add(next.vertical)?,
+        })
+    }
+}
+
+impl CheckedState for Part1SubmarineState {
+    fn checked_apply(self, t: &Translation) -> Option<Self> {
+        Some(Part1SubmarineState(SubmarinePosition {
+            horizontal: self.0.horizontal.checked_add(t.horizontal)?,
+            vertical: self.0.vertical.checked_add(t.vertical)?,
+        }))
+    }
+}
+
+/// Part 2 commands are affine in the aim. Applied to horizontal position `h`,
+/// depth `d` and aim `a`, the transform gives `h + horizontal`,
+/// `d + vertical + a * aim_vertical` and `a * aim_scale + aim`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Affine {
+    pub aim_scale: i64,
+    pub aim: i64,
+    pub horizontal: i64,
+    pub vertical: i64,
+    /// How much each unit of the starting aim adds to the depth.
+    pub aim_vertical: i64,
+}
+
+impl Transform for Affine {
+    const IDENTITY: Self = Affine {
+        aim_scale: 1,
+        aim: 0,
+        horizontal: 0,
+        vertical: 0,
+        aim_vertical: 0,
+    };
+
+    fn then(self, next: Self) -> Self {
+        // `next` sees the aim that `self` leaves, `a * aim_scale + aim`.
+        Affine {
+            aim_scale: self.aim_scale.wrapping_mul(next.aim_scale),
+            aim: next.aim_scale.wrapping_mul(self.aim).wrapping_add(next.aim),
+            horizontal: self.horizontal.wrapping_add(next.horizontal),
+            vertical: self
+                .vertical
+                .wrapping_add(next.vertical)
+                .wrapping_add(self.aim.wrapping_mul(next.aim_vertical)),
+            aim_vertical: self
+                .aim_vertical
+                .wrapping_add(self.aim_scale.wrapping_mul(next.aim_vertical)),
+        }
+    }
+}
+
+impl CheckedTransform for Affine {
+    fn checked_then(self, next: Self) -> Option<Self> {
+        Some(Affine {
+            aim_scale: self.aim_scale.checked_mul(next.aim_scale)?,
+            aim: next
+                .aim_scale
+                .checked_mul(self.aim)?
+                .checked_add(next.aim)?,
+            horizontal: self.horizontal.checked_add(next.horizontal)?,
+            vertical: self
+                .vertical
+                .checked_add(next.vertical)?
+                .checked_add(self.aim.checked_mul(next.aim_vertical)?)?,
+            aim_vertical: self
+                .aim_vertical
+                .checked_add(self.aim_scale.checked_mul(next.aim_vertical)?)?,
+        })
+    }
+}
+
+impl CheckedState for Part2SubmarineState {
+    fn checked_apply(self, t: &Affine) -> Option<Self> {
+        let position = self.position;
+        Some(Part2SubmarineState {
+            position: SubmarinePosition {
+                horizontal: position.horizontal.checked_add(t.horizontal)?,
+                vertical: position
+                    .vertical
+                    .checked_add(t.vertical)?
+                    .checked_add(self.aim.checked_mul(t.aim_vertical)?)?,
+            },
+            aim: self.aim.checked_mul(t.aim_scale)?.checked_add(t.aim)?,
+        })
+    }
+}
+
+impl ComposableState for Part2SubmarineState {
+    type Transform = Affine;
+
+    fn transform(c: &Command) -> Affine {
+        let amount = c.amount as i64;
+        let t = Affine::IDENTITY;
+        match c.direction {
+            Direction::Forward => Affine {
+                horizontal: amount,
+                aim_vertical: amount,
+                ..t
+            },
+            Direction::Back => Affine {
+                horizontal: -amount,
+                aim_vertical: -amount,
+                ..t
+            },
+            Direction::Down => Affine { aim: amount, ..t },
+            Direction::Up => Affine { aim: -amount, ..t },
+            Direction::Aim => Affine {
+                aim_scale: 0,
+                aim: amount,
+                ..t
+            },
+            Direction::TurnLeft
+            | Direction::TurnRight
+            | Direction::PitchUp
+            | Direction::PitchDown => t,
+        }
+    }
+
+    fn apply(self, t: &Affine) -> Self {
+        Part2SubmarineState {
+            position: SubmarinePosition {
+                horizontal: self.position.horizontal.wrapping_add(t.horizontal),
+                vertical: self
+                    .position
+                    .vertical
+                    .wrapping_add(t.vertical)
+                    .wrapping_add(self.aim.wrapping_mul(t.aim_vertical)),
+            },
+            aim: self.aim.wrapping_mul(t.aim_scale).wrapping_add(t.aim),
+        }
+    }
+}
+
+/// Reduce the commands to the single transform that they make up together.
+pub fn reduce<S: ComposableState>(commands: &[Command]) -> S::Transform {
+    commands
+        .iter()
+        .fold(S::Transform::IDENTITY, |t, c| t.then(S::transform(c)))
+}
+
+/// Run the commands from `initial_state`, splitting them into a chunk for each
+/// of `threads` threads to reduce in parallel.
+pub fn execute_parallel<S: ComposableState>(
+    initial_state: S,
+    commands: &[Command],
+    threads: usize,
+) -> S {
+    let chunk_size = commands.len().div_ceil(threads.max(1)).max(1);
+    let transforms: Vec<S::Transform> = thread::scope(|scope| {
+        let handles: Vec<_> = commands
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(|| reduce::<S>(chunk)))
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    let total = transforms
+        .into_iter()
+        .fold(S::Transform::IDENTITY, Transform::then);
+    initial_state.apply(&total)
+}
+
+/// Reduce a program to a single transform. Repeat blocks are raised to their
+/// count by squaring, so the time taken doesn't grow with the count.
+pub fn reduce_program<S: ComposableState>(program: &Program) -> S::Transform {
+    fn reduce_statements<S: ComposableState>(statements: &[Statement]) -> S::Transform {
+        statements
+            .iter()
+            .fold(S::Transform::IDENTITY, |t, statement| match statement {
+                Statement::Command { command, .. } => t.then(S::transform(command)),
+                Statement::Repeat { count, body, .. } => {
+                    t.then(reduce_statements::<S>(body).repeat(*count))
+                }
+            })
+    }
+    reduce_statements::<S>(&program.statements)
+}
+
+/// An interpretation whose transforms can be composed and applied without
+/// wrapping.
+pub trait CheckedState: ComposableState<Transform: CheckedTransform> {
+    /// The state `t` leads to, or `None` if it is beyond the range of the
+    /// state.
+    fn checked_apply(self, t: &Self::Transform) -> Option<Self>;
+}
+
+// Reduce statements to a single transform, or return the line of the first
+// statement whose transform, or whose composition with those before it, is out
+// of range.
+fn checked_reduce<S: CheckedState>(statements: &[Statement]) -> Result<S::Transform, Overflow> {
+    statements
+        .iter()
+        .try_fold(S::Transform::IDENTITY, |t, statement| {
+            let (next, line) = match statement {
+                Statement::Command { command, line } => (Some(S::transform(command)), *line),
+                Statement::Repeat { count, body, line } => {
+                    (checked_reduce::<S>(body)?.checked_repeat(*count), *line)
+                }
+            };
+            next.and_then(|next| t.checked_then(next))
+                .ok_or(Overflow { line })
+        })
+}
+
+/// Run a program from `state` without wrapping, in time that doesn't grow with
+/// the count of any repeat block. The state is checked after each statement at
+/// the top level, and each repeat block is checked as a whole, so the first
+/// line found to go out of range is the one reported.
+pub fn checked_execute_program<S: CheckedState>(
+    state: S,
+    program: &Program,
+) -> Result<S, Overflow> {
+    program
+        .statements
+        .iter()
+        .try_fold(state, |state, statement| {
+            let t = checked_reduce::<S>(std::slice::from_ref(statement))?;
+            state.checked_apply(&t).ok_or(Overflow {
+                line: statement.line(),
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::program::parse_program;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    fn command() -> impl Strategy<Value = Command> {
+        (0..5usize, -10..10).prop_map(|(d, amount)| Command {
+            direction: [
+                Direction::Forward,
+                Direction::Back,
+                Direction::Down,
+                Direction::Up,
+                Direction::Aim,
+            ][d],
+            amount,
+        })
+    }
+
+    // Commands with any amount, which soon overflow under part 2.
+    fn wide_command() -> impl Strategy<Value = Command> {
+        (0..5usize, any::<i32>()).prop_map(|(d, amount)| Command {
+            direction: [
+                Direction::Forward,
+                Direction::Back,
+                Direction::Down,
+                Direction::Up,
+                Direction::Aim,
+            ][d],
+            amount,
+        })
+    }
+
+    fn sequential<S: SubmarineState>(state: S, commands: &[Command]) -> Result<S, Overflow> {
+        commands
+            .iter()
+            .zip(1..)
+            .try_fold(state, |state, (c, line)| {
+                state.try_execute(c).ok_or(Overflow { line })
+            })
+    }
+
+    #[test]
+    fn checked_repeat_blocks() {
+        let program = parse_program("down 1\nrepeat 1000 {\n  forward 2\n  down 1\n}\n").unwrap();
+        let state = Part2SubmarineState::default();
+        assert_eq!(
+            checked_execute_program(state, &program),
+            Ok(program.execute(state))
+        );
+        // Nothing is squared beyond the highest power of two in the count, so
+        // a block that takes the submarine almost to the limit can run once.
+        let program =
+            parse_program("repeat 1 {\n  repeat 4294967295 {\n    forward 2147483647\n  }\n}\n")
+                .unwrap();
+        let state = checked_execute_program(Part1SubmarineState::default(), &program).unwrap();
+        assert_eq!(state.0.horizontal, 4294967295 * 2147483647);
+        let program = parse_program(
+            "forward 1\nrepeat 4294967295 {\n  repeat 4294967295 {\n    back 1\n  }\n}\n",
+        )
+        .unwrap();
+        assert_eq!(
+            checked_execute_program(Part1SubmarineState::default(), &program),
+            Err(Overflow { line: 2 })
+        );
+    }
+
+    #[test]
+    fn repeat_blocks() {
+        let program = parse_program("down 1\nrepeat 1000 {\n  forward 2\n  down 1\n}\n").unwrap();
+        let state = Part2SubmarineState::default();
+        assert_eq!(
+            state.apply(&reduce_program::<Part2SubmarineState>(&program)),
+            program.execute(state)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn parallel_matches_sequential(
+            commands in vec(command(), 0..200),
+            threads in 1..8usize,
+        ) {
+            let state = Part1SubmarineState::default();
+            prop_assert_eq!(
+                execute_parallel(state, &commands, threads),
+                commands.iter().fold(state, |s, c| s.execute(c))
+            );
+            let state = Part2SubmarineState { aim: 3, ..Default::default() };
+            prop_assert_eq!(
+                execute_parallel(state, &commands, threads),
+                commands.iter().fold(state, |s, c| s.execute(c))
+            );
+        }
+
+        #[test]
+        fn checked_matches_sequential(commands in vec(wide_command(), 0..50)) {
+            let program = Program {
+                statements: commands
+                    .iter()
+                    .zip(1..)
+                    .map(|(&command, line)| Statement::Command { command, line })
+                    .collect(),
+            };
+            let state = Part1SubmarineState::default();
+            prop_assert_eq!(
+                checked_execute_program(state, &program),
+                sequential(state, &commands)
+            );
+            let state = Part2SubmarineState::default();
+            prop_assert_eq!(
+                checked_execute_program(state, &program),
+                sequential(state, &commands)
+            );
+        }
+
+        #[test]
+        fn composition_is_associative(a in command(), b in command(), c in command()) {
+            let [a, b, c] = [a, b, c].map(|c| Part2SubmarineState::transform(&c));
+            prop_assert_eq!(a.then(b).then(c), a.then(b.then(c)));
+            prop_assert_eq!(Affine::IDENTITY.then(a), a);
+            prop_assert_eq!(a.then(Affine::IDENTITY), a);
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use crate::Day3;
+use common::{Generator, Rng};
+use std::io::{self, Write};
+
+/// Width of the binary numbers in the real diagnostic report.
+pub const DEFAULT_WIDTH: usize = 12;
+
+/// Write a diagnostic report of `size` random binary numbers that are each
+/// `width` bits wide.
+pub fn generate_report(
+    rng: &mut Rng,
+    size: usize,
+    width: usize,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    let mut line = Vec::with_capacity(width + 1);
+    for _ in 0..size {
+        line.clear();
+        line.extend((0..width).map(|_| if rng.bool() { b'1' } else { b'0' }));
+        line.push(b'\n');
+        out.write_all(&line)?;
+    }
+    Ok(())
+}
+
+impl Generator for Day3 {
+    fn generate(rng: &mut Rng, size: usize, out: &mut dyn Write) -> io::Result<()> {
+        generate_report(rng, size, DEFAULT_WIDTH, out)
+    }
+}
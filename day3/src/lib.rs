@@ -0,0 +1,231 @@
+//! Day 3: Binary Diagnostic
+
+mod generate;
+pub mod natural;
+pub mod rating;
+pub mod reference;
+pub mod report;
+
+pub use generate::{generate_report, DEFAULT_WIDTH};
+pub use natural::Natural;
+pub use rating::{BitCriteria, RatingIndex};
+pub use report::Report;
+
+use common::{Line, ParseError, Solution};
+
+/// Check that a line is a binary number, and return its bits, most significant
+/// first.
+pub fn parse_input_line<'a>(
+    line: &Line<'a>,
+) -> Result<impl Iterator<Item = bool> + 'a, ParseError> {
+    let text = line.text;
+    if let Some((i, c)) = text.char_indices().find(|&(_, c)| c != '0' && c != '1') {
+        return Err(line.error(&text[i..i + c.len_utf8()], "0 or 1"));
+    }
+    Ok(text.bytes().map(|b| b == b'1'))
+}
+
+/// Parse one binary number per line into a packed report. Every line must have
+/// the same number of bits as the first line.
+pub fn parse_input(input: &str) -> Result<Report, ParseError> {
+    let mut report: Option<Report> = None;
+    for line in common::lines(input) {
+        let bits = parse_input_line(&line)?;
+        let width = line.text.len();
+        let report = match &mut report {
+            Some(report) => {
+                if width != report.width() {
+                    let expected = format!("{} bits", report.width());
+                    return Err(line.error(line.text, &expected));
+                }
+                report
+            }
+            None if width == 0 => return Err(line.error(line.text, "a binary number")),
+            None => report.insert(Report::new(width)),
+        };
+        report.push(bits);
+    }
+    report.ok_or_else(|| common::end_of_input(input, "a binary number"))
+}
+
+/// Return the gamma rate times the epsilon rate, built from the most and least
+/// common bit in each position.
+pub fn part1(report: &Report) -> Natural {
+    let mut rates = Report::new(report.width());
+    let most_common: Vec<bool> = report
+        .column_counts()
+        .iter()
+        .map(|&ones| ones * 2 > report.len())
+        .collect();
+    rates.push(most_common.iter().copied());
+    rates.push(most_common.iter().map(|&bit| !bit));
+    Natural::from_words(rates.row(0)) * Natural::from_words(rates.row(1))
+}
+
+/// Narrow the numbers down one bit position at a time, keeping those with the
+/// most common bit (or least common, if `requires_most_common_bit` is false),
+/// until one number remains, and return its row. Ties keep the numbers with a 1
+/// when looking for the most common bit, and a 0 otherwise. `RatingIndex` finds
+/// the same row without copying, and for other tie-break rules.
+pub fn filtered_row(report: &Report, requires_most_common_bit: bool) -> usize {
+    let mut remaining_rows: Vec<usize> = (0..report.len()).collect();
+    for c in 0..report.width() {
+        let num_rows = remaining_rows.len();
+        if num_rows == 1 {
+            break;
+        }
+        let sum = remaining_rows.iter().filter(|&&r| report.bit(r, c)).count();
+        // If every remaining number has the same bit here then the least common
+        // bit doesn't occur at all, so keep all of them.
+        if sum == 0 || sum == num_rows {
+            continue;
+        }
+        let most_common_bit = sum * 2 >= num_rows;
+        let required_bit = most_common_bit == requires_most_common_bit;
+        remaining_rows.retain(|&r| report.bit(r, c) == required_bit);
+    }
+    remaining_rows[0]
+}
+
+/// The number that `filtered_row` narrows the report down to.
+pub fn get_filtered_number(report: &Report, requires_most_common_bit: bool) -> Natural {
+    let row = filtered_row(report, requires_most_common_bit);
+    Natural::from_words(report.row(row))
+}
+
+/// Return the oxygen generator rating times the CO2 scrubber rating.
+pub fn part2(report: &Report) -> Natural {
+    let index = RatingIndex::new(report);
+    let rating = |criteria| Natural::from_words(report.row(index.rating_row(criteria)));
+    let oxygen_generator_rating = rating(BitCriteria::OXYGEN_GENERATOR);
+    let co2_scrubber_rating = rating(BitCriteria::CO2_SCRUBBER);
+    oxygen_generator_rating * co2_scrubber_rating
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: u32 = 3;
+    type Input = Report;
+    type Answer = Natural;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(report: &Self::Input) -> Self::Answer {
+        part1(report)
+    }
+
+    fn part2(report: &Self::Input) -> Self::Answer {
+        part2(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{Generator, Rng};
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    const EXAMPLE: &str = include_str!("example.txt");
+
+    fn binary_numbers() -> impl Strategy<Value = Vec<Vec<u32>>> {
+        // Reports up to 64 bits wide fit in a word per number, and wider ones
+        // have answers too big for a u128.
+        (1..200usize).prop_flat_map(|width| vec(vec(0..2u32, width), 1..100))
+    }
+
+    fn pack(binary_numbers: &[Vec<u32>]) -> Report {
+        let mut report = Report::new(binary_numbers[0].len());
+        for bits in binary_numbers {
+            report.push(bits.iter().map(|&b| b == 1));
+        }
+        report
+    }
+
+    #[test]
+    fn part1_example() {
+        let input = Day3::parse(EXAMPLE).unwrap();
+        assert_eq!(Day3::part1(&input), Natural::from(198));
+    }
+
+    #[test]
+    fn part2_example() {
+        let input = Day3::parse(EXAMPLE).unwrap();
+        assert_eq!(Day3::part2(&input), Natural::from(230));
+    }
+
+    #[test]
+    fn generated_input_parses() {
+        let mut text = Vec::new();
+        Day3::generate(&mut Rng::new(1), 1000, &mut text).unwrap();
+        Day3::parse(std::str::from_utf8(&text).unwrap()).unwrap();
+    }
+
+    // `filtered_row` as it was before it skipped columns where every
+    // remaining number has the same bit.
+    fn filtered_row_without_skip(report: &Report, requires_most_common_bit: bool) -> usize {
+        let mut remaining_rows: Vec<usize> = (0..report.len()).collect();
+        for c in 0..report.width() {
+            let num_rows = remaining_rows.len();
+            if num_rows == 1 {
+                break;
+            }
+            let sum = remaining_rows.iter().filter(|&&r| report.bit(r, c)).count();
+            let most_common_bit = sum * 2 >= num_rows;
+            let required_bit = most_common_bit == requires_most_common_bit;
+            remaining_rows.retain(|&r| report.bit(r, c) == required_bit);
+        }
+        remaining_rows[0]
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of bounds")]
+    fn co2_filter_without_skip_panics_on_duplicate_rows() {
+        // Both rows have the most common bit in the first column, so keeping
+        // those with the least common bit keeps none of them.
+        let duplicates = parse_input("101\n101\n").unwrap();
+        filtered_row_without_skip(&duplicates, false);
+    }
+
+    #[test]
+    fn co2_filter_keeps_duplicate_rows() {
+        // The least common bit after the first column leaves two equal
+        // numbers, which share every remaining bit. Without skipping columns
+        // where the least common bit doesn't occur, the filter would empty.
+        let report = parse_input("011\n000\n011\n000\n000\n").unwrap();
+        assert_eq!(filtered_row(&report, false), 0);
+        assert_eq!(get_filtered_number(&report, false), Natural::from(0b011));
+        let duplicates = parse_input("101\n101\n").unwrap();
+        assert_eq!(
+            get_filtered_number(&duplicates, false),
+            Natural::from(0b101)
+        );
+        assert_eq!(part2(&duplicates), Natural::from(0b101 * 0b101));
+    }
+
+    #[test]
+    fn wide_reports() {
+        let (ones_first, zeros_first) = ("10".repeat(50), "01".repeat(50));
+        let input = format!("{0}\n{0}\n{1}\n", ones_first, zeros_first);
+        let report = Day3::parse(&input).unwrap();
+        assert_eq!(report.width(), 100);
+        let product = "357097343168664505675991576075250511404832563328844317531250";
+        assert_eq!(Day3::part1(&report).to_string(), product);
+        assert_eq!(Day3::part2(&report).to_string(), product);
+        let report = parse_input(&format!("{}\n", "1".repeat(64))).unwrap();
+        assert_eq!(part1(&report), Natural::default());
+        assert_eq!(part2(&report), Natural::from((u64::MAX as u128).pow(2)));
+    }
+
+    proptest! {
+        #[test]
+        fn parts_match_reference(binary_numbers in binary_numbers()) {
+            let report = pack(&binary_numbers);
+            prop_assert_eq!(part1(&report), reference::part1(&binary_numbers));
+            prop_assert_eq!(part2(&report), reference::part2(&binary_numbers));
+        }
+    }
+}
@@ -0,0 +1,129 @@
+//! Non-negative integers of any size, for the answers to reports too wide for
+//! their products to fit in a `u128`. Only what the answers need is provided:
+//! building a number from packed words, multiplying and printing in decimal.
+
+use std::fmt;
+use std::ops::Mul;
+
+/// A non-negative integer stored as 64-bit limbs, least significant first, with
+/// no zero limbs at the most significant end.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Natural {
+    limbs: Vec<u64>,
+}
+
+impl Natural {
+    /// The number made up of `words`, most significant first, as in
+    /// `Report::row`.
+    pub fn from_words(words: &[u64]) -> Self {
+        let mut limbs: Vec<u64> = words.iter().rev().copied().collect();
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        Natural { limbs }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    // Divide in place by a single limb, returning the remainder.
+    fn div_rem_limb(&mut self, divisor: u64) -> u64 {
+        let mut remainder = 0u128;
+        for limb in self.limbs.iter_mut().rev() {
+            let value = remainder << 64 | *limb as u128;
+            *limb = (value / divisor as u128) as u64;
+            remainder = value % divisor as u128;
+        }
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+        remainder as u64
+    }
+}
+
+impl From<u128> for Natural {
+    fn from(n: u128) -> Self {
+        Natural::from_words(&[(n >> 64) as u64, n as u64])
+    }
+}
+
+impl Mul for &Natural {
+    type Output = Natural;
+
+    // Long multiplication, one limb of `rhs` at a time.
+    fn mul(self, rhs: &Natural) -> Natural {
+        let mut limbs = vec![0u64; self.limbs.len() + rhs.limbs.len()];
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u128;
+            for (j, &b) in rhs.limbs.iter().enumerate() {
+                let value = a as u128 * b as u128 + limbs[i + j] as u128 + carry;
+                limbs[i + j] = value as u64;
+                carry = value >> 64;
+            }
+            limbs[i + rhs.limbs.len()] = carry as u64;
+        }
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        Natural { limbs }
+    }
+}
+
+impl Mul for Natural {
+    type Output = Natural;
+
+    fn mul(self, rhs: Natural) -> Natural {
+        &self * &rhs
+    }
+}
+
+// Largest power of ten that fits in a limb, for printing 19 digits at a time.
+const DIGITS_PER_CHUNK: usize = 19;
+const CHUNK: u64 = 10u64.pow(DIGITS_PER_CHUNK as u32);
+
+impl fmt::Display for Natural {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut n = self.clone();
+        let mut chunks = Vec::new();
+        while !n.is_zero() {
+            chunks.push(n.div_rem_limb(CHUNK));
+        }
+        let digits = match chunks.split_last() {
+            None => "0".to_string(),
+            Some((first, rest)) => rest.iter().rev().fold(first.to_string(), |s, chunk| {
+                s + &format!("{:0w$}", chunk, w = DIGITS_PER_CHUNK)
+            }),
+        };
+        f.pad_integral(true, "", &digits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn prints_in_decimal() {
+        assert_eq!(Natural::default().to_string(), "0");
+        assert_eq!(Natural::from_words(&[0, 0, 7]).to_string(), "7");
+        assert_eq!(Natural::from(u128::MAX).to_string(), u128::MAX.to_string());
+        // 2^128 = 340282366920938463463374607431768211456
+        let big = Natural::from_words(&[1, 0, 0]);
+        assert_eq!(big.to_string(), "340282366920938463463374607431768211456");
+        assert_eq!(
+            (&big * &big).to_string(),
+            "115792089237316195423570985008687907853269984665640564039457584007913129639936"
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn matches_u128(a in any::<u64>(), b in any::<u64>()) {
+            let product = Natural::from(a as u128) * Natural::from(b as u128);
+            prop_assert_eq!(product.clone(), Natural::from(a as u128 * b as u128));
+            prop_assert_eq!(product.to_string(), (a as u128 * b as u128).to_string());
+        }
+    }
+}
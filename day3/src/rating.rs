@@ -0,0 +1,155 @@
+//! Finding life support ratings without copying the report, by sorting it once
+//! and then narrowing a range of it one bit position at a time.
+
+use crate::Report;
+
+/// Which bit to keep at each position while narrowing the report down to a
+/// single number.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BitCriteria {
+    /// Keep the numbers with the most common bit, or the least common.
+    pub most_common: bool,
+    /// Keep the numbers with a 1 when both bits are equally common, or a 0.
+    pub on_tie: bool,
+}
+
+impl BitCriteria {
+    pub const OXYGEN_GENERATOR: BitCriteria = BitCriteria {
+        most_common: true,
+        on_tie: true,
+    };
+
+    pub const CO2_SCRUBBER: BitCriteria = BitCriteria {
+        most_common: false,
+        on_tie: false,
+    };
+
+    /// Whether to keep the numbers with a 1, given how many have each bit.
+    pub fn keep_ones(self, ones: usize, zeros: usize) -> bool {
+        if ones == zeros {
+            self.on_tie
+        } else {
+            (ones > zeros) == self.most_common
+        }
+    }
+}
+
+/// The rows of a report sorted by their numbers, so that the numbers sharing
+/// any prefix are next to each other, and those with a 0 after the prefix come
+/// before those with a 1.
+pub struct RatingIndex<'a> {
+    report: &'a Report,
+    order: Vec<usize>,
+}
+
+impl<'a> RatingIndex<'a> {
+    pub fn new(report: &'a Report) -> Self {
+        let mut order: Vec<usize> = (0..report.len()).collect();
+        // The sort is stable, so equal numbers stay in report order.
+        order.sort_by(|&a, &b| report.row(a).cmp(report.row(b)));
+        RatingIndex { report, order }
+    }
+
+    /// The row that `criteria` narrows the report down to. Positions where
+    /// every remaining number has the same bit are skipped, and if several
+    /// equal numbers remain at the end, the first in the report is chosen, just
+    /// as `filtered_row` does.
+    pub fn rating_row(&self, criteria: BitCriteria) -> usize {
+        let (mut start, mut end) = (0, self.order.len());
+        for c in 0..self.report.width() {
+            if end - start == 1 {
+                break;
+            }
+            let range = &self.order[start..end];
+            let zeros = range.partition_point(|&r| !self.report.bit(r, c));
+            let ones = range.len() - zeros;
+            if zeros == 0 || ones == 0 {
+                continue;
+            }
+            if criteria.keep_ones(ones, zeros) {
+                start += zeros;
+            } else {
+                end = start + zeros;
+            }
+        }
+        self.order[start]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{filtered_row, parse_input};
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    const EXAMPLE: &str = include_str!("example.txt");
+
+    const ALL_CRITERIA: [BitCriteria; 4] = [
+        BitCriteria::OXYGEN_GENERATOR,
+        BitCriteria::CO2_SCRUBBER,
+        BitCriteria {
+            most_common: true,
+            on_tie: false,
+        },
+        BitCriteria {
+            most_common: false,
+            on_tie: true,
+        },
+    ];
+
+    // Narrow the rows down by copying the ones that are kept.
+    fn naive_rating_row(report: &Report, criteria: BitCriteria) -> usize {
+        let mut rows: Vec<usize> = (0..report.len()).collect();
+        for c in 0..report.width() {
+            let ones = rows.iter().filter(|&&r| report.bit(r, c)).count();
+            let zeros = rows.len() - ones;
+            if rows.len() > 1 && ones > 0 && zeros > 0 {
+                let bit = criteria.keep_ones(ones, zeros);
+                rows.retain(|&r| report.bit(r, c) == bit);
+            }
+        }
+        rows[0]
+    }
+
+    #[test]
+    fn example_ratings() {
+        let report = parse_input(EXAMPLE).unwrap();
+        let index = RatingIndex::new(&report);
+        let rating = |criteria| report.value(index.rating_row(criteria)).unwrap();
+        assert_eq!(rating(BitCriteria::OXYGEN_GENERATOR), 23);
+        assert_eq!(rating(BitCriteria::CO2_SCRUBBER), 10);
+    }
+
+    fn report() -> impl Strategy<Value = Report> {
+        // Narrow reports have plenty of ties and repeated numbers, and wide
+        // ones take several words per number.
+        (1..80usize).prop_flat_map(|width| {
+            vec(vec(any::<bool>(), width), 1..200).prop_map(move |rows| {
+                let mut report = Report::new(width);
+                for row in rows {
+                    report.push(row);
+                }
+                report
+            })
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn matches_filtered_row(report in report()) {
+            let index = RatingIndex::new(&report);
+            prop_assert_eq!(
+                index.rating_row(BitCriteria::OXYGEN_GENERATOR),
+                filtered_row(&report, true)
+            );
+            prop_assert_eq!(
+                index.rating_row(BitCriteria::CO2_SCRUBBER),
+                filtered_row(&report, false)
+            );
+            for criteria in ALL_CRITERIA {
+                prop_assert_eq!(index.rating_row(criteria), naive_rating_row(&report, criteria));
+            }
+        }
+    }
+}
@@ -0,0 +1,71 @@
+//! Straightforward implementations of each part, for checking the real
+//! solutions against. Numbers are handled as strings of '0' and '1'.
+
+use crate::Natural;
+
+fn to_strings(binary_numbers: &[Vec<u32>]) -> Vec<String> {
+    binary_numbers
+        .iter()
+        .map(|bits| {
+            bits.iter()
+                .map(|b| if *b == 1 { '1' } else { '0' })
+                .collect()
+        })
+        .collect()
+}
+
+// Convert a string of '0' and '1' to a number, 64 digits at a time from the
+// least significant end.
+fn to_natural(binary: &str) -> Natural {
+    let mut words: Vec<u64> = binary
+        .as_bytes()
+        .rchunks(64)
+        .map(|chunk| u64::from_str_radix(std::str::from_utf8(chunk).unwrap(), 2).unwrap())
+        .collect();
+    words.reverse();
+    Natural::from_words(&words)
+}
+
+fn count_ones(numbers: &[String], position: usize) -> usize {
+    numbers
+        .iter()
+        .filter(|n| n.as_bytes()[position] == b'1')
+        .count()
+}
+
+pub fn part1(binary_numbers: &[Vec<u32>]) -> Natural {
+    let numbers = to_strings(binary_numbers);
+    let mut gamma = String::new();
+    let mut epsilon = String::new();
+    for position in 0..numbers[0].len() {
+        let ones = count_ones(&numbers, position);
+        let zeros = numbers.len() - ones;
+        gamma.push(if ones > zeros { '1' } else { '0' });
+        epsilon.push(if ones > zeros { '0' } else { '1' });
+    }
+    to_natural(&gamma) * to_natural(&epsilon)
+}
+
+// Keep the numbers whose bit at each position passes `keep_ones`, which is given
+// the number of ones and zeros. Positions where every number has the same bit
+// are skipped.
+fn rating(binary_numbers: &[Vec<u32>], keep_ones: fn(usize, usize) -> bool) -> Natural {
+    let mut numbers = to_strings(binary_numbers);
+    let mut position = 0;
+    while numbers.len() > 1 && position < numbers[0].len() {
+        let ones = count_ones(&numbers, position);
+        let zeros = numbers.len() - ones;
+        if ones > 0 && zeros > 0 {
+            let bit = if keep_ones(ones, zeros) { b'1' } else { b'0' };
+            numbers.retain(|n| n.as_bytes()[position] == bit);
+        }
+        position += 1;
+    }
+    to_natural(&numbers[0])
+}
+
+pub fn part2(binary_numbers: &[Vec<u32>]) -> Natural {
+    let oxygen_generator_rating = rating(binary_numbers, |ones, zeros| ones >= zeros);
+    let co2_scrubber_rating = rating(binary_numbers, |ones, zeros| ones < zeros);
+    oxygen_generator_rating * co2_scrubber_rating
+}
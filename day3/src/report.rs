@@ -0,0 +1,216 @@
+//! Diagnostic reports packed into 64-bit words, so that bits in the same
+//! column can be counted many rows at a time.
+
+/// Binary numbers of the same width, packed into words. Each number takes as
+/// many words as its width needs, most significant word first, with the number
+/// aligned to the least significant end. A number up to 64 bits wide is
+/// therefore stored as its own value.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Report {
+    width: usize,
+    words_per_row: usize,
+    len: usize,
+    words: Vec<u64>,
+}
+
+// Transpose a 64 x 64 matrix of bits, where bit 63 of each word is its first
+// column, so that word `i` afterwards holds what was column `i`.
+fn transpose(block: &mut [u64; 64]) {
+    let mut j = 32;
+    let mut mask = 0x0000_0000_FFFF_FFFFu64;
+    while j != 0 {
+        let mut k = 0;
+        while k < 64 {
+            let t = (block[k] ^ (block[k | j] >> j)) & mask;
+            block[k] ^= t;
+            block[k | j] ^= t << j;
+            k = ((k | j) + 1) & !j;
+        }
+        j >>= 1;
+        mask ^= mask << j;
+    }
+}
+
+// Add the number of ones in each column of a block of rows to `counts`.
+fn add_block(block: &mut [u64; 64], counts: &mut [usize]) {
+    transpose(block);
+    for (count, column) in counts.iter_mut().zip(block.iter()) {
+        *count += column.count_ones() as usize;
+    }
+}
+
+impl Report {
+    /// An empty report of numbers that are `width` bits wide.
+    pub fn new(width: usize) -> Self {
+        Report {
+            width,
+            words_per_row: width.div_ceil(64),
+            len: 0,
+            words: Vec::new(),
+        }
+    }
+
+    /// Number of bits in each number.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Number of numbers in the report.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // Bits of padding above the most significant bit of each number.
+    fn padding(&self) -> usize {
+        self.words_per_row * 64 - self.width
+    }
+
+    /// Add a number given by its bits, most significant first.
+    ///
+    /// Panics if there isn't exactly one bit for each column.
+    pub fn push(&mut self, bits: impl IntoIterator<Item = bool>) {
+        let mut word = 0u64;
+        let mut position = self.padding();
+        for bit in bits {
+            word = word << 1 | bit as u64;
+            position += 1;
+            if position.is_multiple_of(64) {
+                self.words.push(word);
+                word = 0;
+            }
+        }
+        assert!(
+            position == self.padding() + self.width,
+            "number should be {} bits wide",
+            self.width
+        );
+        self.len += 1;
+    }
+
+    /// The words holding the number in row `row`, most significant first.
+    pub fn row(&self, row: usize) -> &[u64] {
+        let start = row * self.words_per_row;
+        &self.words[start..start + self.words_per_row]
+    }
+
+    /// Whether the number in row `row` has a 1 in column `column`, counting
+    /// from the most significant bit.
+    pub fn bit(&self, row: usize, column: usize) -> bool {
+        let position = self.padding() + column;
+        self.row(row)[position / 64] >> (63 - position % 64) & 1 == 1
+    }
+
+    /// The number in row `row`, or `None` if the report is too wide for it to
+    /// fit in 64 bits.
+    pub fn value(&self, row: usize) -> Option<u64> {
+        match self.row(row) {
+            [] => Some(0),
+            [word] => Some(*word),
+            _ => None,
+        }
+    }
+
+    /// The number of ones in each column, from the most significant bit.
+    ///
+    /// Blocks of 64 rows are transposed so that each column of a block is a
+    /// single word whose ones can be counted at once.
+    pub fn column_counts(&self) -> Vec<usize> {
+        let mut counts = vec![0; self.words_per_row * 64];
+        for w in 0..self.words_per_row {
+            let column_words = self.words[w..].iter().step_by(self.words_per_row);
+            let mut block = [0u64; 64];
+            let mut filled = 0;
+            for &word in column_words.take(self.len) {
+                block[filled] = word;
+                filled += 1;
+                if filled == 64 {
+                    add_block(&mut block, &mut counts[w * 64..(w + 1) * 64]);
+                    filled = 0;
+                }
+            }
+            if filled > 0 {
+                block[filled..].fill(0);
+                add_block(&mut block, &mut counts[w * 64..(w + 1) * 64]);
+            }
+        }
+        counts.split_off(self.padding())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    #[test]
+    fn transposes() {
+        let mut block = [0u64; 64];
+        for (i, word) in block.iter_mut().enumerate() {
+            *word = (i as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        }
+        let original = block;
+        transpose(&mut block);
+        for (i, word) in block.iter().enumerate() {
+            for (j, original) in original.iter().enumerate() {
+                assert_eq!(
+                    word >> (63 - j) & 1,
+                    original >> (63 - i) & 1,
+                    "({}, {})",
+                    i,
+                    j
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn packs_numbers() {
+        let mut report = Report::new(5);
+        report.push([true, false, true, true, false]);
+        report.push([false; 5]);
+        assert_eq!((report.len(), report.width()), (2, 5));
+        assert_eq!(report.value(0), Some(0b10110));
+        assert!(report.bit(0, 0) && !report.bit(0, 1) && report.bit(0, 3));
+        assert_eq!(report.column_counts(), [1, 0, 1, 1, 0]);
+        let mut wide = Report::new(70);
+        wide.push((0..70).map(|i| i == 0 || i == 69));
+        assert_eq!(wide.row(0), [1 << 5, 1]);
+        assert_eq!(wide.value(0), None);
+        assert!(wide.bit(0, 0) && wide.bit(0, 69) && !wide.bit(0, 6));
+    }
+
+    #[test]
+    #[should_panic(expected = "number should be 3 bits wide")]
+    fn rejects_wrong_width() {
+        Report::new(3).push([true, false]);
+    }
+
+    fn rows() -> impl Strategy<Value = Vec<Vec<bool>>> {
+        (1..200usize).prop_flat_map(|width| vec(vec(any::<bool>(), width), 0..150))
+    }
+
+    proptest! {
+        #[test]
+        fn counts_match_bits(rows in rows()) {
+            let width = rows.first().map_or(1, Vec::len);
+            let mut report = Report::new(width);
+            for row in &rows {
+                report.push(row.iter().copied());
+            }
+            let expected: Vec<usize> = (0..width)
+                .map(|c| rows.iter().filter(|row| row[c]).count())
+                .collect();
+            prop_assert_eq!(report.column_counts(), expected);
+            for (r, row) in rows.iter().enumerate() {
+                for (c, &bit) in row.iter().enumerate() {
+                    prop_assert_eq!(report.bit(r, c), bit);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use crate::Day4;
+use common::{Generator, Rng};
+use std::io::{self, Write};
+
+// Like the real input, every number from 0 to 99 is called once, and each board
+// is filled with distinct numbers from the same range.
+const NUM_NUMBERS: u32 = 100;
+
+// The size is the number of boards.
+impl Generator for Day4 {
+    fn generate(rng: &mut Rng, size: usize, out: &mut dyn Write) -> io::Result<()> {
+        let mut numbers: Vec<u32> = (0..NUM_NUMBERS).collect();
+        rng.shuffle(&mut numbers);
+        let called: Vec<String> = numbers.iter().map(|n| n.to_string()).collect();
+        writeln!(out, "{}", called.join(","))?;
+        for _ in 0..size {
+            rng.shuffle(&mut numbers);
+            writeln!(out)?;
+            for row in numbers[..25].chunks(5) {
+                let row: Vec<String> = row.iter().map(|n| format!("{:>2}", n)).collect();
+                writeln!(out, "{}", row.join(" "))?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,233 @@
+//! Day 4: Giant Squid
+
+mod generate;
+pub mod reference;
+
+use common::{Line, ParseError, Solution};
+
+#[derive(Default, Copy, Clone, Debug)]
+pub struct BingoTile {
+    pub number: u32,
+    pub marked: bool,
+}
+
+impl BingoTile {
+    pub fn new(n: u32) -> Self {
+        BingoTile {
+            number: n,
+            marked: false,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BingoBoard {
+    pub tiles: [[BingoTile; 5]; 5],
+    pub bingo: bool,
+}
+
+impl BingoBoard {
+    pub fn new(tiles: [[BingoTile; 5]; 5]) -> Self {
+        BingoBoard {
+            tiles,
+            bingo: false,
+        }
+    }
+
+    /// Mark any matching tiles with the given number.
+    pub fn mark(&mut self, n: u32) {
+        self.tiles.iter_mut().for_each(|row| {
+            row.iter_mut().for_each(|tile| {
+                if tile.number == n {
+                    tile.marked = true;
+                }
+            })
+        });
+    }
+
+    /// The board has a bingo if any row or column is fully marked. Diagonals
+    /// are not considered.
+    pub fn check_bingo(&mut self) {
+        self.bingo |= (0..5).any(|r| (0..5).all(|c| self.tiles[r][c].marked));
+        self.bingo |= (0..5).any(|c| (0..5).all(|r| self.tiles[r][c].marked));
+    }
+}
+
+/// Parse text into problem input. First line will contain a list of numbers,
+/// the second line will be a blank line, and the rest of the lines will contain
+/// 5x5 bingo boards separated by blank lines.
+pub fn parse_input(input: &str) -> Result<(Vec<u32>, Vec<BingoBoard>), ParseError> {
+    let mut lines = common::lines(input);
+
+    let first_line = lines
+        .next()
+        .ok_or_else(|| common::end_of_input(input, "a list of numbers"))?;
+    let numbers: Vec<u32> = first_line
+        .text
+        .split(',')
+        .map(|s| first_line.parse::<u32>(s, "a number"))
+        .collect::<Result<Vec<u32>, ParseError>>()?;
+
+    let boards: Vec<BingoBoard> = lines
+        .collect::<Vec<Line>>()
+        .chunks(6)
+        .map(|chunk| {
+            if !chunk[0].text.is_empty() {
+                return Err(chunk[0].error(chunk[0].text, "a blank line"));
+            }
+            let mut tiles: [[BingoTile; 5]; 5] = [[BingoTile::default(); 5]; 5];
+            for (i, line) in chunk.iter().skip(1).enumerate() {
+                let row = line
+                    .text
+                    .split_whitespace()
+                    .map(|s| line.parse::<u32>(s, "a number"))
+                    .collect::<Result<Vec<u32>, ParseError>>()?;
+                if row.len() != 5 {
+                    return Err(line.error(line.text, "5 numbers"));
+                }
+                for (j, n) in row.iter().enumerate() {
+                    tiles[i][j] = BingoTile::new(*n);
+                }
+            }
+            if chunk.len() < 6 {
+                return Err(common::end_of_input(input, "5 rows of bingo numbers"));
+            }
+            Ok(BingoBoard::new(tiles))
+        })
+        .collect::<Result<Vec<BingoBoard>, ParseError>>()?;
+    if boards.is_empty() {
+        return Err(common::end_of_input(input, "a bingo board"));
+    }
+
+    Ok((numbers, boards))
+}
+
+/// Return the first board that wins the soonest when the numbers are called,
+/// and the number that is called when the board wins.
+pub fn get_first_winning_board(numbers: &[u32], boards: &[BingoBoard]) -> (u32, BingoBoard) {
+    let mut boards: Vec<BingoBoard> = boards.to_owned();
+    let mut winning_board = None;
+    let mut winning_number = None;
+    for n in numbers {
+        boards.iter_mut().for_each(|board| board.mark(*n));
+        boards.iter_mut().for_each(|board| board.check_bingo());
+        if boards.iter().any(|board| board.bingo) {
+            winning_number = Some(*n);
+            winning_board = Some(boards.iter().find(|board| board.bingo).unwrap().clone());
+            break;
+        }
+    }
+    (winning_number.unwrap(), winning_board.unwrap())
+}
+
+/// Return the first board that wins the latest when the numbers are called,
+/// and the number that is called when the board wins.
+pub fn get_last_winning_board(numbers: &[u32], boards: &[BingoBoard]) -> (u32, BingoBoard) {
+    let mut boards: Vec<BingoBoard> = boards.to_owned();
+    let mut winning_number = None;
+    let mut winning_board = None;
+    for n in numbers {
+        boards.iter_mut().for_each(|board| board.mark(*n));
+        boards.iter_mut().for_each(|board| board.check_bingo());
+        if boards.iter().any(|board| board.bingo) {
+            winning_number = Some(*n);
+            winning_board = Some(boards.iter().find(|board| board.bingo).unwrap().clone());
+            boards.retain(|board| !board.bingo);
+        }
+    }
+    (winning_number.unwrap(), winning_board.unwrap())
+}
+
+pub fn get_sum_of_unmarked_numbers(board: &BingoBoard) -> u32 {
+    board
+        .tiles
+        .iter()
+        .flatten()
+        .filter(|tile| !tile.marked)
+        .map(|tile| tile.number)
+        .sum()
+}
+
+/// Part 1: Return the first number to get a bingo times the sum of all unmarked numbers of the first winning board.
+pub fn part1(numbers: &[u32], boards: &[BingoBoard]) -> u32 {
+    let (winning_number, winning_board) = get_first_winning_board(numbers, boards);
+    winning_number * get_sum_of_unmarked_numbers(&winning_board)
+}
+
+/// Part 2: Return the last number to get a bingo times the sum of all unmarked numbers of the last winning board.
+pub fn part2(numbers: &[u32], boards: &[BingoBoard]) -> u32 {
+    let (winning_number, winning_board) = get_last_winning_board(numbers, boards);
+    winning_number * get_sum_of_unmarked_numbers(&winning_board)
+}
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: u32 = 4;
+    type Input = (Vec<u32>, Vec<BingoBoard>);
+    type Answer = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1((numbers, boards): &Self::Input) -> Self::Answer {
+        part1(numbers, boards)
+    }
+
+    fn part2((numbers, boards): &Self::Input) -> Self::Answer {
+        part2(numbers, boards)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{Generator, Rng};
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    const EXAMPLE: &str = include_str!("example.txt");
+
+    // Every number in the pool is called, so every board eventually wins.
+    fn bingo_game() -> impl Strategy<Value = (Vec<u32>, Vec<BingoBoard>)> {
+        (25..60u32).prop_flat_map(|pool| {
+            let numbers = Just((0..pool).collect::<Vec<u32>>()).prop_shuffle();
+            let board = vec(0..pool, 25).prop_map(|tile_numbers| {
+                let mut tiles = [[BingoTile::default(); 5]; 5];
+                for (i, n) in tile_numbers.iter().enumerate() {
+                    tiles[i / 5][i % 5] = BingoTile::new(*n);
+                }
+                BingoBoard::new(tiles)
+            });
+            (numbers, vec(board, 1..10))
+        })
+    }
+
+    #[test]
+    fn part1_example() {
+        let input = Day4::parse(EXAMPLE).unwrap();
+        assert_eq!(Day4::part1(&input), 4512);
+    }
+
+    #[test]
+    fn part2_example() {
+        let input = Day4::parse(EXAMPLE).unwrap();
+        assert_eq!(Day4::part2(&input), 1924);
+    }
+
+    #[test]
+    fn generated_input_parses() {
+        let mut text = Vec::new();
+        Day4::generate(&mut Rng::new(1), 1000, &mut text).unwrap();
+        Day4::parse(std::str::from_utf8(&text).unwrap()).unwrap();
+    }
+
+    proptest! {
+        #[test]
+        fn parts_match_reference((numbers, boards) in bingo_game()) {
+            prop_assert_eq!(part1(&numbers, &boards), reference::part1(&numbers, &boards));
+            prop_assert_eq!(part2(&numbers, &boards), reference::part2(&numbers, &boards));
+        }
+    }
+}
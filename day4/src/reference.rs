@@ -0,0 +1,64 @@
+//! Straightforward implementations of each part, for checking the real
+//! solutions against. Instead of marking boards as numbers are called, each
+//! board works out the turn that it would win on.
+
+use crate::BingoBoard;
+
+// Return the index of the called number that completes a row or column of the
+// board, if any.
+fn winning_turn(numbers: &[u32], board: &BingoBoard) -> Option<usize> {
+    let turn_called = |n: u32| numbers.iter().position(|&m| m == n);
+    let rows = (0..5).map(|r| {
+        (0..5)
+            .map(|c| board.tiles[r][c].number)
+            .collect::<Vec<u32>>()
+    });
+    let cols = (0..5).map(|c| {
+        (0..5)
+            .map(|r| board.tiles[r][c].number)
+            .collect::<Vec<u32>>()
+    });
+    rows.chain(cols)
+        .filter_map(|line| {
+            line.iter()
+                .map(|&n| turn_called(n))
+                .collect::<Option<Vec<usize>>>()
+                .map(|turns| *turns.iter().max().unwrap())
+        })
+        .min()
+}
+
+fn score(numbers: &[u32], board: &BingoBoard, turn: usize) -> u32 {
+    let called = &numbers[..=turn];
+    let unmarked: u32 = board
+        .tiles
+        .iter()
+        .flatten()
+        .filter(|tile| !called.contains(&tile.number))
+        .map(|tile| tile.number)
+        .sum();
+    numbers[turn] * unmarked
+}
+
+// Boards that win on the same turn are ordered by their position in the input.
+fn winners(numbers: &[u32], boards: &[BingoBoard]) -> Vec<(usize, usize)> {
+    let mut winners: Vec<(usize, usize)> = boards
+        .iter()
+        .enumerate()
+        .filter_map(|(i, board)| winning_turn(numbers, board).map(|turn| (turn, i)))
+        .collect();
+    winners.sort();
+    winners
+}
+
+pub fn part1(numbers: &[u32], boards: &[BingoBoard]) -> u32 {
+    let (turn, i) = winners(numbers, boards)[0];
+    score(numbers, &boards[i], turn)
+}
+
+pub fn part2(numbers: &[u32], boards: &[BingoBoard]) -> u32 {
+    let winners = winners(numbers, boards);
+    let last_turn = winners.last().unwrap().0;
+    let (turn, i) = *winners.iter().find(|(turn, _)| *turn == last_turn).unwrap();
+    score(numbers, &boards[i], turn)
+}
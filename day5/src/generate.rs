@@ -0,0 +1,44 @@
+use crate::Day5;
+use common::{Generator, Rng};
+use std::io::{self, Write};
+
+// Like the real input, every point lies within a 1000x1000 grid.
+const GRID_SIZE: i64 = 1000;
+
+// Line segments are equally likely to be horizontal, vertical or diagonal.
+impl Generator for Day5 {
+    fn generate(rng: &mut Rng, size: usize, out: &mut dyn Write) -> io::Result<()> {
+        for _ in 0..size {
+            let (x1, y1) = (
+                rng.below(GRID_SIZE as u64) as i64,
+                rng.below(GRID_SIZE as u64) as i64,
+            );
+            let (dx, dy) = match rng.below(3) {
+                0 => (1, 0),
+                1 => (0, 1),
+                _ => (1, if rng.bool() { 1 } else { -1 }),
+            };
+            let (dx, dy) = if rng.bool() { (dx, dy) } else { (-dx, -dy) };
+            // Clip the length so that the segment stays inside the grid.
+            let max_length = [(dx, x1), (dy, y1)]
+                .iter()
+                .map(|&(d, p)| match d {
+                    1 => GRID_SIZE - 1 - p,
+                    -1 => p,
+                    _ => GRID_SIZE,
+                })
+                .min()
+                .unwrap();
+            let length = rng.range(0, max_length.min(GRID_SIZE / 2));
+            writeln!(
+                out,
+                "{},{} -> {},{}",
+                x1,
+                y1,
+                x1 + dx * length,
+                y1 + dy * length
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,293 @@
+//! Day 5: Hydrothermal Venture
+
+mod generate;
+pub mod reference;
+
+use common::{Line, ParseError, Solution};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    /// Parse a point such as `8,0` from `s`, which is a slice of the given line.
+    pub fn from_string(line: &Line, s: &str) -> Result<Self, ParseError> {
+        let mut parts = s.split(',');
+        let x = parts
+            .next()
+            .ok_or_else(|| line.error(s, "an X coordinate"))
+            .and_then(|part| line.parse::<i32>(part, "an X coordinate"))?;
+        let y = parts
+            .next()
+            .ok_or_else(|| line.error(&s[s.len()..], "a comma and a Y coordinate"))
+            .and_then(|part| line.parse::<i32>(part, "a Y coordinate"))?;
+        Ok(Point { x, y })
+    }
+}
+
+/// A line segment that is horizontal, vertical, or diagonal at 45 degrees.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LineSegment {
+    pub start: Point,
+    pub end: Point,
+}
+
+impl LineSegment {
+    /// Parse a line segment such as `8,0 -> 0,8`. Line segments must be
+    /// horizontal, vertical, or diagonal at 45 degrees.
+    pub fn from_string(line: &Line) -> Result<Self, ParseError> {
+        let mut parts = line.text.split(" -> ");
+        let start = Point::from_string(
+            line,
+            parts
+                .next()
+                .ok_or_else(|| line.error(line.text, "a start point"))?,
+        )?;
+        let end = Point::from_string(
+            line,
+            parts
+                .next()
+                .ok_or_else(|| line.error(line.end(), "' -> ' and an end point"))?,
+        )?;
+        let line_segment = LineSegment { start, end };
+        if !line_segment.is_horizontal()
+            && !line_segment.is_vertical()
+            && line_segment.get_max_x() - line_segment.get_min_x()
+                != line_segment.get_max_y() - line_segment.get_min_y()
+        {
+            return Err(line.error(
+                line.text,
+                "a horizontal, vertical or 45 degree diagonal line segment",
+            ));
+        }
+        Ok(line_segment)
+    }
+
+    pub fn get_min_x(&self) -> i32 {
+        self.start.x.min(self.end.x)
+    }
+
+    pub fn get_max_x(&self) -> i32 {
+        self.start.x.max(self.end.x)
+    }
+
+    pub fn get_min_y(&self) -> i32 {
+        self.start.y.min(self.end.y)
+    }
+
+    pub fn get_max_y(&self) -> i32 {
+        self.start.y.max(self.end.y)
+    }
+
+    pub fn is_vertical(&self) -> bool {
+        self.start.x == self.end.x
+    }
+
+    pub fn is_horizontal(&self) -> bool {
+        self.start.y == self.end.y
+    }
+}
+
+pub struct LineSegmentCollection {
+    line_segments: Vec<LineSegment>,
+}
+
+impl LineSegmentCollection {
+    /// There must be at least one line segment.
+    pub fn new(line_segments: Vec<LineSegment>) -> Self {
+        assert!(!line_segments.is_empty());
+        LineSegmentCollection { line_segments }
+    }
+
+    /// Parse one line segment per line.
+    pub fn from_string(s: &str) -> Result<Self, ParseError> {
+        let line_segments: Vec<LineSegment> = common::lines(s)
+            .map(|line| LineSegment::from_string(&line))
+            .collect::<Result<Vec<LineSegment>, ParseError>>()?;
+        if line_segments.is_empty() {
+            return Err(common::end_of_input(s, "a line segment"));
+        }
+        Ok(LineSegmentCollection { line_segments })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &LineSegment> {
+        self.line_segments.iter()
+    }
+
+    pub fn get_min_x(&self) -> i32 {
+        self.iter().map(|ls| ls.get_min_x()).min().unwrap()
+    }
+
+    pub fn get_max_x(&self) -> i32 {
+        self.iter().map(|ls| ls.get_max_x()).max().unwrap()
+    }
+
+    pub fn get_min_y(&self) -> i32 {
+        self.iter().map(|ls| ls.get_min_y()).min().unwrap()
+    }
+
+    pub fn get_max_y(&self) -> i32 {
+        self.iter().map(|ls| ls.get_max_y()).max().unwrap()
+    }
+}
+
+/// Count of line segments covering each point in the bounding box of a
+/// collection of line segments.
+pub struct PointGrid {
+    grid: Vec<Vec<usize>>,
+    offset_x: i32,
+    offset_y: i32,
+}
+
+impl PointGrid {
+    pub fn new(line_segments: &LineSegmentCollection) -> Self {
+        let total_min_x = line_segments.get_min_x();
+        let total_max_x = line_segments.get_max_x();
+        let total_min_y = line_segments.get_min_y();
+        let total_max_y = line_segments.get_max_y();
+        let grid_width = total_max_x - total_min_x + 1;
+        let grid_height = total_max_y - total_min_y + 1;
+        PointGrid {
+            grid: vec![vec![0; grid_width as usize]; grid_height as usize],
+            offset_x: total_min_x,
+            offset_y: total_min_y,
+        }
+    }
+
+    /// The line segment must lie within the grid.
+    pub fn draw_line_segment(&mut self, line_segment: &LineSegment) {
+        let start_x = line_segment.start.x - self.offset_x;
+        let end_x = line_segment.end.x - self.offset_x;
+        let start_y = line_segment.start.y - self.offset_y;
+        let end_y = line_segment.end.y - self.offset_y;
+        let min_x = start_x.min(end_x);
+        let max_x = start_x.max(end_x);
+        let min_y = start_y.min(end_y);
+        let max_y = start_y.max(end_y);
+        if line_segment.is_vertical() {
+            for y in min_y..=max_y {
+                self.grid[y as usize][start_x as usize] += 1;
+            }
+        } else if line_segment.is_horizontal() {
+            for x in min_x..=max_x {
+                self.grid[start_y as usize][x as usize] += 1;
+            }
+        } else {
+            // Assume the line segment is diagonal at 45 degrees
+            let num_points = max_x - min_x + 1;
+            for offset in 0..num_points {
+                let x = {
+                    let direction = if start_x < end_x { 1 } else { -1 };
+                    start_x + offset * direction
+                };
+                let y = {
+                    let direction = if start_y < end_y { 1 } else { -1 };
+                    start_y + offset * direction
+                };
+                self.grid[y as usize][x as usize] += 1;
+            }
+        }
+    }
+
+    /// Return the number of points covered by more than one line segment.
+    pub fn get_num_intersecting_points(&self) -> usize {
+        self.grid
+            .iter()
+            .map(|row| row.iter().filter(|&&x| x > 1).count())
+            .sum()
+    }
+}
+
+/// Return the number of points where horizontal and vertical line segments intersect.
+pub fn part1(line_segments: &LineSegmentCollection) -> usize {
+    let mut point_grid = PointGrid::new(line_segments);
+    for ls in line_segments
+        .iter()
+        .filter(|ls| ls.is_horizontal() || ls.is_vertical())
+    {
+        point_grid.draw_line_segment(ls);
+    }
+    point_grid.get_num_intersecting_points()
+}
+
+/// Return the number of points where any two line segments intersect.
+pub fn part2(line_segments: &LineSegmentCollection) -> usize {
+    let mut point_grid = PointGrid::new(line_segments);
+    for ls in line_segments.iter() {
+        point_grid.draw_line_segment(ls);
+    }
+    point_grid.get_num_intersecting_points()
+}
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    const DAY: u32 = 5;
+    type Input = LineSegmentCollection;
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        LineSegmentCollection::from_string(input)
+    }
+
+    fn part1(line_segments: &Self::Input) -> Self::Answer {
+        part1(line_segments)
+    }
+
+    fn part2(line_segments: &Self::Input) -> Self::Answer {
+        part2(line_segments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{Generator, Rng};
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    const EXAMPLE: &str = include_str!("example.txt");
+
+    fn line_segment() -> impl Strategy<Value = LineSegment> {
+        (-10..10, -10..10, 0..4usize, -10..10).prop_map(|(x, y, kind, length)| {
+            let (dx, dy) = [(1, 0), (0, 1), (1, 1), (1, -1)][kind];
+            LineSegment {
+                start: Point { x, y },
+                end: Point {
+                    x: x + dx * length,
+                    y: y + dy * length,
+                },
+            }
+        })
+    }
+
+    #[test]
+    fn part1_example() {
+        let input = Day5::parse(EXAMPLE).unwrap();
+        assert_eq!(Day5::part1(&input), 5);
+    }
+
+    #[test]
+    fn part2_example() {
+        let input = Day5::parse(EXAMPLE).unwrap();
+        assert_eq!(Day5::part2(&input), 12);
+    }
+
+    #[test]
+    fn generated_input_parses() {
+        let mut text = Vec::new();
+        Day5::generate(&mut Rng::new(1), 1000, &mut text).unwrap();
+        Day5::parse(std::str::from_utf8(&text).unwrap()).unwrap();
+    }
+
+    proptest! {
+        #[test]
+        fn parts_match_reference(line_segments in vec(line_segment(), 1..50)) {
+            let line_segments = LineSegmentCollection { line_segments };
+            prop_assert_eq!(part1(&line_segments), reference::part1(&line_segments));
+            prop_assert_eq!(part2(&line_segments), reference::part2(&line_segments));
+        }
+    }
+}
@@ -0,0 +1,35 @@
+//! Straightforward implementations of each part, for checking the real
+//! solutions against. Points are counted in a map instead of a dense grid.
+
+use crate::{LineSegment, LineSegmentCollection};
+use std::collections::HashMap;
+
+fn count_overlaps<'a>(line_segments: impl Iterator<Item = &'a LineSegment>) -> usize {
+    let mut counts: HashMap<(i32, i32), usize> = HashMap::new();
+    for ls in line_segments {
+        let dx = (ls.end.x - ls.start.x).signum();
+        let dy = (ls.end.y - ls.start.y).signum();
+        let (mut x, mut y) = (ls.start.x, ls.start.y);
+        loop {
+            *counts.entry((x, y)).or_default() += 1;
+            if (x, y) == (ls.end.x, ls.end.y) {
+                break;
+            }
+            x += dx;
+            y += dy;
+        }
+    }
+    counts.values().filter(|&&count| count > 1).count()
+}
+
+pub fn part1(line_segments: &LineSegmentCollection) -> usize {
+    count_overlaps(
+        line_segments
+            .iter()
+            .filter(|ls| ls.start.x == ls.end.x || ls.start.y == ls.end.y),
+    )
+}
+
+pub fn part2(line_segments: &LineSegmentCollection) -> usize {
+    count_overlaps(line_segments.iter())
+}
@@ -0,0 +1,16 @@
+use crate::Day6;
+use common::{Generator, Rng};
+use std::io::{self, Write};
+
+// Like the real input, every fish starts with a timer from 1 to 5.
+impl Generator for Day6 {
+    fn generate(rng: &mut Rng, size: usize, out: &mut dyn Write) -> io::Result<()> {
+        for i in 0..size {
+            if i > 0 {
+                out.write_all(b",")?;
+            }
+            write!(out, "{}", rng.range(1, 5))?;
+        }
+        writeln!(out)
+    }
+}
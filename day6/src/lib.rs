@@ -0,0 +1,108 @@
+//! Day 6: Lanternfish
+
+mod generate;
+pub mod reference;
+
+use common::{Line, ParseError, Solution};
+
+fn parse_timer(line: &Line, s: &str) -> Result<u32, ParseError> {
+    let expected = "a timer from 0 to 8";
+    match line.parse::<u32>(s, expected)? {
+        n if n <= 8 => Ok(n),
+        _ => Err(line.error(s, expected)),
+    }
+}
+
+/// Parse comma-separated fish timers into the number of fish with each timer
+/// value.
+pub fn parse_input(input: &str) -> Result<[usize; 9], ParseError> {
+    let mut nums_array = [0; 9];
+    for line in common::lines(input) {
+        for s in line.text.split(',') {
+            nums_array[parse_timer(&line, s)? as usize] += 1;
+        }
+    }
+    Ok(nums_array)
+}
+
+/// Return the number of fish after `num_days`, given the number of fish with
+/// each timer value.
+pub fn simulate_population(pop_counts: &[usize; 9], num_days: usize) -> usize {
+    let mut pop_counts = *pop_counts;
+    for _ in 0..num_days {
+        pop_counts.rotate_left(1);
+        pop_counts[6] += pop_counts[8];
+    }
+    pop_counts.iter().sum()
+}
+
+pub fn part1(pop_counts: &[usize; 9]) -> usize {
+    simulate_population(pop_counts, 80)
+}
+
+pub fn part2(pop_counts: &[usize; 9]) -> usize {
+    simulate_population(pop_counts, 256)
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    const DAY: u32 = 6;
+    type Input = [usize; 9];
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(pop_counts: &Self::Input) -> Self::Answer {
+        part1(pop_counts)
+    }
+
+    fn part2(pop_counts: &Self::Input) -> Self::Answer {
+        part2(pop_counts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{Generator, Rng};
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    const EXAMPLE: &str = include_str!("example.txt");
+
+    #[test]
+    fn part1_example() {
+        let input = Day6::parse(EXAMPLE).unwrap();
+        assert_eq!(Day6::part1(&input), 5934);
+    }
+
+    #[test]
+    fn part2_example() {
+        let input = Day6::parse(EXAMPLE).unwrap();
+        assert_eq!(Day6::part2(&input), 26984457539);
+    }
+
+    #[test]
+    fn generated_input_parses() {
+        let mut text = Vec::new();
+        Day6::generate(&mut Rng::new(1), 1000, &mut text).unwrap();
+        Day6::parse(std::str::from_utf8(&text).unwrap()).unwrap();
+    }
+
+    proptest! {
+        #[test]
+        fn simulation_matches_reference(timers in vec(0..9u32, 0..20), num_days in 0..80usize) {
+            let mut pop_counts = [0; 9];
+            for timer in &timers {
+                pop_counts[*timer as usize] += 1;
+            }
+            prop_assert_eq!(
+                simulate_population(&pop_counts, num_days),
+                reference::simulate_population(&timers, num_days)
+            );
+        }
+    }
+}
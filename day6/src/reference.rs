@@ -0,0 +1,20 @@
+//! Straightforward implementation of the simulation, for checking the real
+//! solution against. Every fish is tracked individually.
+
+/// Return the number of fish after `num_days`, given the timer of each fish.
+pub fn simulate_population(timers: &[u32], num_days: usize) -> usize {
+    let mut timers = timers.to_vec();
+    for _ in 0..num_days {
+        let mut new_fish = 0;
+        for timer in timers.iter_mut() {
+            if *timer == 0 {
+                *timer = 6;
+                new_fish += 1;
+            } else {
+                *timer -= 1;
+            }
+        }
+        timers.extend(std::iter::repeat_n(8, new_fish));
+    }
+    timers.len()
+}
@@ -0,0 +1,20 @@
+use crate::Day7;
+use common::{Generator, Rng};
+use std::io::{self, Write};
+
+// Like the real input, positions range up to about 2000 and most crabs are
+// near the low end.
+const MAX_POSITION: u64 = 2000;
+
+impl Generator for Day7 {
+    fn generate(rng: &mut Rng, size: usize, out: &mut dyn Write) -> io::Result<()> {
+        for i in 0..size {
+            if i > 0 {
+                out.write_all(b",")?;
+            }
+            let limit = rng.below(MAX_POSITION) + 1;
+            write!(out, "{}", rng.below(limit))?;
+        }
+        writeln!(out)
+    }
+}
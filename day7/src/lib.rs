@@ -0,0 +1,127 @@
+//! Day 7: The Treachery of Whales
+
+mod generate;
+pub mod reference;
+
+use common::{ParseError, Solution};
+
+/// Parse comma-separated crab positions.
+pub fn parse_input(input: &str) -> Result<Vec<u32>, ParseError> {
+    let mut nums = Vec::new();
+    for line in common::lines(input) {
+        for s in line.text.split(',') {
+            nums.push(line.parse::<u32>(s, "a crab position")?);
+        }
+    }
+    if nums.is_empty() {
+        return Err(common::end_of_input(input, "a crab position"));
+    }
+    Ok(nums)
+}
+
+/// Return the least fuel needed to line up every crab, where each step costs
+/// one fuel.
+pub fn part1(nums: &[u32]) -> u128 {
+    let mut nums = nums.to_owned();
+    nums.sort();
+    let median = nums[nums.len() / 2];
+    nums.iter().map(|n| n.abs_diff(median) as u128).sum()
+}
+
+/// Return the least fuel needed to line up every crab, where each step costs
+/// one more fuel than the last.
+pub fn part2(nums: &[u32]) -> u128 {
+    let sum: u64 = nums.iter().map(|&n| n as u64).sum();
+    let len = nums.len() as u64;
+    fn get_fuel_cost(num_steps: u32) -> u128 {
+        let num_steps = num_steps as u128;
+        (num_steps * (num_steps + 1)) / 2
+    }
+    let get_total_fuel_cost = |position: u32| {
+        nums.iter()
+            .map(|n| get_fuel_cost(n.abs_diff(position)))
+            .sum::<u128>()
+    };
+    // The floor and ceiling of the mean, which lie between the outermost crabs
+    // and so fit in a position.
+    let floor = (sum / len) as u32;
+    let ceil = sum.div_ceil(len) as u32;
+    get_total_fuel_cost(floor).min(get_total_fuel_cost(ceil))
+}
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: u32 = 7;
+    type Input = Vec<u32>;
+    type Answer = u128;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(nums: &Self::Input) -> Self::Answer {
+        part1(nums)
+    }
+
+    fn part2(nums: &Self::Input) -> Self::Answer {
+        part2(nums)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{Generator, Rng};
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    const EXAMPLE: &str = include_str!("example.txt");
+
+    #[test]
+    fn part1_example() {
+        let input = Day7::parse(EXAMPLE).unwrap();
+        assert_eq!(Day7::part1(&input), 37);
+    }
+
+    #[test]
+    fn part2_example() {
+        let input = Day7::parse(EXAMPLE).unwrap();
+        assert_eq!(Day7::part2(&input), 168);
+    }
+
+    #[test]
+    fn generated_input_parses() {
+        let mut text = Vec::new();
+        Day7::generate(&mut Rng::new(1), 1000, &mut text).unwrap();
+        Day7::parse(std::str::from_utf8(&text).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn solves_large_generated_input() {
+        let mut text = Vec::new();
+        Day7::generate(&mut Rng::new(1), 1_000_000, &mut text).unwrap();
+        let input = Day7::parse(std::str::from_utf8(&text).unwrap()).unwrap();
+        let (part1, part2) = (Day7::part1(&input), Day7::part2(&input));
+        // Part 2 used to overflow a 32-bit answer at this size.
+        assert!(part2 > u32::MAX as u128);
+        assert!(part1 < part2);
+    }
+
+    #[test]
+    fn extreme_positions() {
+        let nums = [0, u32::MAX, u32::MAX];
+        assert_eq!(part1(&nums), u32::MAX as u128);
+        let steps = u32::MAX as u128 / 3;
+        let cost = |n: u128| n * (n + 1) / 2;
+        assert_eq!(part2(&nums), cost(steps * 2) + 2 * cost(steps));
+    }
+
+    proptest! {
+        #[test]
+        fn parts_match_reference(nums in vec(0..500u32, 1..100)) {
+            prop_assert_eq!(part1(&nums), reference::part1(&nums));
+            prop_assert_eq!(part2(&nums), reference::part2(&nums));
+        }
+    }
+}
@@ -0,0 +1,23 @@
+//! Straightforward implementations of each part, for checking the real
+//! solutions against. Every position between the outermost crabs is tried.
+
+fn min_total_fuel_cost(nums: &[u32], get_fuel_cost: fn(u128) -> u128) -> u128 {
+    let min = *nums.iter().min().unwrap();
+    let max = *nums.iter().max().unwrap();
+    (min..=max)
+        .map(|position| {
+            nums.iter()
+                .map(|&n| get_fuel_cost(n.abs_diff(position) as u128))
+                .sum::<u128>()
+        })
+        .min()
+        .unwrap()
+}
+
+pub fn part1(nums: &[u32]) -> u128 {
+    min_total_fuel_cost(nums, |num_steps| num_steps)
+}
+
+pub fn part2(nums: &[u32]) -> u128 {
+    min_total_fuel_cost(nums, |num_steps| num_steps * (num_steps + 1) / 2)
+}